@@ -1,15 +1,60 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use curunir::config::Profile;
 use curunir::constants;
 use curunir::load_object;
-use curunir::requests::*;
+use curunir::structures::board::Board;
 use curunir::structures::coordinate::Coordinate;
+use curunir::structures::direction::Direction;
+use curunir::structures::evaluation::Evaluation;
+use curunir::structures::transposition_table::TranspositionTable;
 
+use std::alloc::{GlobalAlloc, Layout, System};
 use std::cmp::max;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Search depth is this divided by the number of snakes, the fixed depth the server used before timing its search
+const EXPONENT: i32 = 13;
+
+// Counts heap allocations, to compare copying boards with making and unmaking moves
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// Returns the number of allocations made while running f
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let start = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - start
+}
 
 pub fn area_controlled_bench(c: &mut Criterion) {
     let board = load_object!(Board, "test_board-03", constants::_TEST_PATH);
+    let ruleset = load_object!(Ruleset, "test_board-03", constants::_TEST_PATH);
+
+    c.bench_function("area_controlled", |b| {
+        b.iter(|| board.area_controlled(&ruleset))
+    });
+}
+
+pub fn body_collision_bench(c: &mut Criterion) {
+    let board = black_box(load_object!(Board, "test_board-03", constants::_TEST_PATH));
+    let pos = black_box(Coordinate::new(5, 5));
 
-    c.bench_function("area_controlled", |b| b.iter(|| board.area_controlled()));
+    c.bench_function("body_collision", |b| b.iter(|| board.body_collision(pos)));
 }
 
 pub fn body_collision_with_bench(c: &mut Criterion) {
@@ -29,14 +74,18 @@ pub fn check_area_bench(c: &mut Criterion) {
         constants::_TEST_PATH
     ));
 
+    let ruleset = load_object!(Ruleset, "check_area_closed-01", constants::_TEST_PATH);
+
     c.bench_function("check_area", |b| {
         b.iter(|| {
             board.longest_path(
+                &ruleset,
                 Coordinate::new(7, 10),
                 0,
                 30,
-                &mut Vec::with_capacity(45),
+                &mut vec![false; board.tile_count()],
                 0,
+                constants::MAX_HEALTH,
             )
         })
     });
@@ -45,9 +94,8 @@ pub fn check_area_bench(c: &mut Criterion) {
 pub fn get_option_bench(c: &mut Criterion) {
     let board = load_object!(Board, "simple-01", constants::_TEST_PATH);
     let snake = black_box(&board.get_snakes()[0]);
-    let value = black_box(0);
 
-    c.bench_function("get_options", |b| b.iter(|| snake.get_option(value)));
+    c.bench_function("get_options", |b| b.iter(|| snake.get_options()));
 }
 
 pub fn game_step_bench(c: &mut Criterion) {
@@ -59,31 +107,139 @@ pub fn game_step_bench(c: &mut Criterion) {
     });
 }
 
+// One search step, copying the board versus making and unmaking the move in place
+pub fn make_move_bench(c: &mut Criterion) {
+    let board = black_box(load_object!(Board, "test_board-03", constants::_TEST_PATH));
+    let ruleset = load_object!(Ruleset, "test_board-03", constants::_TEST_PATH);
+    let moves: Vec<Direction> = board
+        .get_snakes()
+        .iter()
+        .map(|snake| snake.get_options()[0])
+        .collect();
+
+    let clone_step = |board: &Board| {
+        let mut new_board = board.clone();
+        for (i, &direction) in moves.iter().enumerate() {
+            let pos = new_board.wrap(new_board.get_snakes()[i].get_move(direction));
+            new_board.get_snakes_mut()[i].move_to(pos);
+        }
+        new_board.game_step(&ruleset);
+        new_board
+    };
+    let mut in_place = board.clone();
+
+    println!(
+        "allocations per step: clone and game_step {}, make_move and unmake_move {}",
+        count_allocations(|| {
+            clone_step(&board);
+        }),
+        count_allocations(|| {
+            let undo = in_place.make_move(&ruleset, &moves);
+            in_place.unmake_move(undo);
+        })
+    );
+
+    c.bench_function("clone_game_step", |b| b.iter(|| clone_step(&board)));
+    c.bench_function("make_unmake_move", |b| {
+        b.iter(|| {
+            let undo = in_place.make_move(&ruleset, &moves);
+            in_place.unmake_move(undo);
+        })
+    });
+}
+
 pub fn minimax_bench(c: &mut Criterion) {
     let board = black_box(load_object!(Board, "test_board-03", constants::_TEST_PATH));
     let current = black_box(0);
-    let max_depth = black_box(max(
-        constants::EXPONENT / board.get_snakes().len() as i32,
-        1,
-    ));
+    let max_depth = black_box(max(EXPONENT / board.get_snakes().len() as i32, 1));
     let ruleset = load_object!(Ruleset, "test_board-03", constants::_TEST_PATH);
+    let profile = Profile::default();
 
     c.bench_function("minimax", |b| {
-        b.iter(|| board.clone().minimax(&ruleset, current, max_depth))
+        b.iter(|| {
+            let table = TranspositionTable::new(0);
+            board
+                .clone()
+                .minimax(&ruleset, &profile, &table, current, max_depth)
+        })
+    });
+}
+
+// Same search as minimax_bench, answering repeated positions from a transposition table
+pub fn minimax_table_bench(c: &mut Criterion) {
+    let board = black_box(load_object!(Board, "test_board-03", constants::_TEST_PATH));
+    let current = black_box(0);
+    let max_depth = black_box(max(EXPONENT / board.get_snakes().len() as i32, 1));
+    let ruleset = load_object!(Ruleset, "test_board-03", constants::_TEST_PATH);
+    let profile = Profile::default();
+
+    c.bench_function("minimax_table", |b| {
+        b.iter(|| {
+            let table = TranspositionTable::new(constants::TABLE_SIZE);
+            board
+                .clone()
+                .minimax(&ruleset, &profile, &table, current, max_depth)
+        })
     });
 }
 
 pub fn minimax_8_bench(c: &mut Criterion) {
     let board = black_box(load_object!(Board, "test_board-05", constants::_TEST_PATH));
     let current = black_box(0);
-    let max_depth = black_box(max(
-        constants::EXPONENT / board.get_snakes().len() as i32,
-        1,
-    ));
+    let max_depth = black_box(max(EXPONENT / board.get_snakes().len() as i32, 1));
     let ruleset = load_object!(Ruleset, "test_board-05", constants::_TEST_PATH);
+    let profile = Profile::default();
 
-    c.bench_function("minimax", |b| {
-        b.iter(|| board.clone().minimax(&ruleset, current, max_depth))
+    c.bench_function("minimax_8", |b| {
+        b.iter(|| {
+            let table = TranspositionTable::new(0);
+            board
+                .clone()
+                .minimax(&ruleset, &profile, &table, current, max_depth)
+        })
+    });
+}
+
+// Same positions and depths as minimax_bench and minimax_8_bench, with the paranoid search
+pub fn paranoid_bench(c: &mut Criterion) {
+    let board = black_box(load_object!(Board, "test_board-03", constants::_TEST_PATH));
+    let current = black_box(0);
+    let max_depth = black_box(max(EXPONENT / board.get_snakes().len() as i32, 1));
+    let ruleset = load_object!(Ruleset, "test_board-03", constants::_TEST_PATH);
+    let profile = Profile::default();
+
+    c.bench_function("paranoid", |b| {
+        b.iter(|| {
+            board.clone().paranoid(
+                &ruleset,
+                &profile,
+                current,
+                max_depth,
+                Evaluation::MIN,
+                Evaluation::MAX,
+            )
+        })
+    });
+}
+
+pub fn paranoid_8_bench(c: &mut Criterion) {
+    let board = black_box(load_object!(Board, "test_board-05", constants::_TEST_PATH));
+    let current = black_box(0);
+    let max_depth = black_box(max(EXPONENT / board.get_snakes().len() as i32, 1));
+    let ruleset = load_object!(Ruleset, "test_board-05", constants::_TEST_PATH);
+    let profile = Profile::default();
+
+    c.bench_function("paranoid_8", |b| {
+        b.iter(|| {
+            board.clone().paranoid(
+                &ruleset,
+                &profile,
+                current,
+                max_depth,
+                Evaluation::MIN,
+                Evaluation::MAX,
+            )
+        })
     });
 }
 
@@ -98,11 +254,16 @@ pub fn open_directions_bench(c: &mut Criterion) {
 
 criterion_group!(
     benches,
+    body_collision_bench,
     body_collision_with_bench,
     get_option_bench,
     game_step_bench,
+    make_move_bench,
     minimax_bench,
+    minimax_table_bench,
     minimax_8_bench,
+    paranoid_bench,
+    paranoid_8_bench,
     open_directions_bench,
     check_area_bench,
     area_controlled_bench,
@@ -0,0 +1,124 @@
+use std::env;
+use std::process;
+
+use curunir::config::Config;
+use curunir::constants::CONFIG_PATH;
+use curunir::structures::arena::{Arena, Player};
+use curunir::structures::ruleset::Ruleset;
+
+const USAGE: &str = "Usage: arena [options] PLAYER...
+
+Plays one game offline and prints every turn's moves, the winner and the turn count
+
+Players: curunir, paranoid, mcts, expected, random, hungry
+
+Options:
+    --seed N       Seeds the spawn points, food, royale shrinks and bots (default 0)
+    --size N       Width and height of the board (default 11)
+    --timeout MS   Time each copy of Curunir has to move (default 100)
+    --turns N      Games still going after N turns are a draw (default 1000)
+    --ruleset NAME standard, royale, constrictor or wrapped (default standard)
+    --config PATH  Config file for Curunir (default config.json if it exists)";
+
+// Prints the usage and exits with an error
+fn usage(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(1);
+}
+
+// Parses the value given after option
+fn parse<T: std::str::FromStr>(option: &str, value: Option<String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| usage(&format!("{} needs a number", option)))
+}
+
+fn main() {
+    let mut seed = 0;
+    let mut size = 11;
+    let mut timeout = 100;
+    let mut max_turns = 1000;
+    let mut ruleset_name = String::from("standard");
+    let mut config_path = None;
+    let mut players = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = parse(&arg, args.next()),
+            "--size" => size = parse(&arg, args.next()),
+            "--timeout" => timeout = parse(&arg, args.next()),
+            "--turns" => max_turns = parse(&arg, args.next()),
+            "--ruleset" => {
+                ruleset_name = args
+                    .next()
+                    .unwrap_or_else(|| usage("--ruleset needs a name"))
+            }
+            "--config" => {
+                config_path = Some(
+                    args.next()
+                        .unwrap_or_else(|| usage("--config needs a path")),
+                )
+            }
+            "-h" | "--help" => usage("Offline game runner"),
+            name => match Player::from_name(name) {
+                Some(player) => players.push(player),
+                None => usage(&format!("Unknown player: {}", name)),
+            },
+        }
+    }
+
+    if players.is_empty() || players.len() > Arena::max_snakes() {
+        usage(&format!(
+            "Between 1 and {} players are needed",
+            Arena::max_snakes()
+        ));
+    }
+    if size < 5 {
+        usage("The board needs a size of at least 5");
+    }
+
+    // Curunir is tuned the same way as the server
+    let config = match config_path {
+        Some(path) => Some(path),
+        None if std::path::Path::new(CONFIG_PATH).exists() => Some(String::from(CONFIG_PATH)),
+        None => None,
+    };
+    if let Some(path) = config {
+        match Config::load(&path) {
+            Ok(config) => Config::set(config),
+            Err(e) => usage(&format!("Can't load {}: {}", path, e)),
+        }
+    }
+
+    // The standard settings, with hazards for royale games
+    let ruleset = Ruleset::new(
+        ruleset_name,
+        String::from("arena"),
+        15,
+        1,
+        14,
+        25,
+        false,
+        false,
+        false,
+        false,
+    );
+
+    let arena = Arena::new(ruleset, size, size, timeout, max_turns, seed);
+    let result = arena.play(&players);
+
+    for (turn, moves) in result.get_moves().iter().enumerate() {
+        let moves: Vec<String> = moves
+            .iter()
+            .map(|(seat, direction)| format!("{} {}", seat, direction))
+            .collect();
+        println!("turn {}: {}", turn, moves.join(", "));
+    }
+
+    match result.get_winner() {
+        Some(seat) => println!("winner: seat {} ({})", seat, players[seat].get_name()),
+        None => println!("winner: none"),
+    }
+    println!("turns: {}", result.get_turns());
+}
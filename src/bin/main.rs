@@ -1,10 +1,17 @@
 use actix_web::{get, post, web, App, HttpResponse, HttpServer};
 use index_response::IndexResponse;
+use logged_move::LoggedMove;
 use move_request::MoveRequest;
 use move_response::MoveResponse;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::env;
+use std::path::Path;
+use std::time::Instant;
 
+use curunir::config::Config;
 use curunir::constants::*;
 use curunir::requests::*;
+use curunir::structures::game_state::GameRegistry;
 
 // Index response
 #[get("/")]
@@ -14,38 +21,81 @@ async fn index() -> HttpResponse {
 
 // Game start
 #[post("/start")]
-async fn start() -> HttpResponse {
+async fn start(data: web::Json<MoveRequest>, games: web::Data<GameRegistry>) -> HttpResponse {
     println!("Start");
+    games.start(data.get_game().get_id());
     HttpResponse::Ok().body("")
 }
 
 // Game move response
 #[post("/move")]
-async fn game_move(data: web::Json<MoveRequest>) -> HttpResponse {
+#[allow(clippy::absurd_extreme_comparisons)]
+async fn game_move(
+    data: web::Json<MoveRequest>,
+    pool: web::Data<ThreadPool>,
+    games: web::Data<GameRegistry>,
+) -> HttpResponse {
     if LOG_LEVEL >= 1 {
         println!("Move");
     }
+    let received = Instant::now();
+    let request = data.into_inner();
+    // Kept to be logged with the answer
+    let logged_request = if LOG_MOVES {
+        Some(request.clone())
+    } else {
+        None
+    };
     // Get data from MoveRequest
-    let (input_game, turn, input_board, you) = data.into_inner().into_values();
+    let (input_game, turn, input_board, you) = request.into_values();
+    let state = games.get(input_game.get_id());
     // Create Board from InputBoard
+    let ids = input_board.get_snake_ids(&you);
     let board = input_board.into_board(you, turn);
+    // Search on the shared pool, with what's remembered about the game
+    let mut game = input_game.into_game();
+    game.set_thread_pool(pool.into_inner());
     // Respond with direction
-    let direction = input_game.into_game().calculate_move(board);
+    let direction = state.lock().unwrap().calculate_move(&mut game, board, ids);
+    if let Some(request) = logged_request {
+        let path = LoggedMove::log_path(request.get_game().get_id());
+        let time = received.elapsed().as_millis() as u64;
+        if let Err(e) = LoggedMove::new(request, direction, time).append(&path) {
+            println!("Can't log move to {}: {}", path, e);
+        }
+    }
     HttpResponse::Ok().json(MoveResponse::new(direction, String::from("Hi!")))
 }
 
 // Game end
 #[post("/end")]
-async fn end() -> HttpResponse {
+async fn end(data: web::Json<MoveRequest>, games: web::Data<GameRegistry>) -> HttpResponse {
     println!("End");
+    games.end(data.get_game().get_id());
     HttpResponse::Ok().body("")
 }
 
 // Start web server
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    HttpServer::new(|| {
+    // Tuning comes from the file given as the first argument, or CONFIG_PATH if it exists
+    let config_path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from(CONFIG_PATH));
+    if env::args().nth(1).is_some() || Path::new(&config_path).exists() {
+        println!("Config: {}", config_path);
+        Config::set(Config::load(&config_path)?);
+    }
+
+    // One search pool for every request, instead of new threads each move
+    let pool = web::Data::new(search_pool());
+    // Games in progress, shared by every worker
+    let games = web::Data::new(GameRegistry::new());
+
+    HttpServer::new(move || {
         App::new()
+            .app_data(pool.clone())
+            .app_data(games.clone())
             .service(index)
             .service(start)
             .service(game_move)
@@ -56,6 +106,15 @@ async fn main() -> std::io::Result<()> {
     .await
 }
 
+// Builds the pool searches run on, with SEARCH_THREADS threads
+fn search_pool() -> ThreadPool {
+    ThreadPoolBuilder::new()
+        .num_threads(SEARCH_THREADS)
+        .thread_name(|i| format!("search-{}", i))
+        .build()
+        .unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,7 +137,13 @@ mod tests {
     async fn test_move_post() {
         let data = load_object!(MoveRequest, "simple-02", _TEST_PATH);
 
-        let mut app = test::init_service(App::new().service(game_move)).await;
+        let mut app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(search_pool()))
+                .app_data(web::Data::new(GameRegistry::new()))
+                .service(game_move),
+        )
+        .await;
         let req = test::TestRequest::post()
             .set_json(&data)
             .uri("/move")
@@ -88,4 +153,51 @@ mod tests {
         println!("{}", resp.status());
         assert!(resp.status().is_success());
     }
+
+    #[actix_rt::test]
+    async fn test_start_move_end() {
+        let games = web::Data::new(GameRegistry::new());
+
+        let mut app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(search_pool()))
+                .app_data(games.clone())
+                .service(start)
+                .service(game_move)
+                .service(end),
+        )
+        .await;
+        for (uri, name) in [
+            ("/start", "history-01-turn1"),
+            ("/move", "history-01-turn1"),
+            ("/move", "history-01-turn2"),
+        ] {
+            let data = load_object!(MoveRequest, name, _TEST_PATH);
+            let req = test::TestRequest::post()
+                .set_json(&data)
+                .uri(uri)
+                .to_request();
+            assert!(test::call_service(&mut app, req)
+                .await
+                .status()
+                .is_success());
+        }
+
+        assert_eq!(games.len(), 1);
+        assert_eq!(
+            games.get("history-01").lock().unwrap().get_history("a"),
+            [curunir::structures::direction::Direction::Left]
+        );
+
+        let data = load_object!(MoveRequest, "history-01-turn3", _TEST_PATH);
+        let req = test::TestRequest::post()
+            .set_json(&data)
+            .uri("/end")
+            .to_request();
+        assert!(test::call_service(&mut app, req)
+            .await
+            .status()
+            .is_success());
+        assert!(games.is_empty());
+    }
 }
@@ -0,0 +1,121 @@
+use std::env;
+use std::path::Path;
+use std::process;
+
+use curunir::config::Config;
+use curunir::constants::CONFIG_PATH;
+use curunir::requests::game_export::GameExport;
+use curunir::structures::direction::Direction;
+use curunir::structures::post_mortem::{find_divergence, review_game};
+
+const USAGE: &str = "Usage: post_mortem [options] GAME SNAKE
+
+Steps through a game exported by the official engine as SNAKE, a snake's name
+or id, deciding every move again with this build. Prints each turn's move played,
+the move chosen now and the moves that survive, then the last turn where the move
+played died while another survived
+
+Options:
+    --depth N      Depth of the search each move's survival is checked with (default 6)
+    --config PATH  Config file for Curunir (default config.json if it exists)";
+
+// Prints the usage and exits with an error
+fn usage(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(1);
+}
+
+// Returns the directions separated by spaces
+fn join(directions: &[Direction]) -> String {
+    let directions: Vec<String> = directions.iter().map(Direction::to_string).collect();
+    directions.join(" ")
+}
+
+fn main() {
+    let mut depth = 6;
+    let mut config_path = None;
+    let mut positional = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--depth" => {
+                depth = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_else(|| usage("--depth needs a number"))
+            }
+            "--config" => {
+                config_path = Some(
+                    args.next()
+                        .unwrap_or_else(|| usage("--config needs a path")),
+                )
+            }
+            "-h" | "--help" => usage("Game post-mortem"),
+            _ => positional.push(arg),
+        }
+    }
+
+    let (path, snake) = match positional.as_slice() {
+        [path, snake] => (path, snake),
+        _ => usage("A game and a snake are needed"),
+    };
+
+    // Curunir is tuned the same way as the server
+    let config = match config_path {
+        Some(path) => Some(path),
+        None if Path::new(CONFIG_PATH).exists() => Some(String::from(CONFIG_PATH)),
+        None => None,
+    };
+    if let Some(path) = config {
+        match Config::load(&path) {
+            Ok(config) => Config::set(config),
+            Err(e) => usage(&format!("Can't load {}: {}", path, e)),
+        }
+    }
+
+    let export =
+        GameExport::load(path).unwrap_or_else(|e| usage(&format!("Can't read {}: {}", path, e)));
+    let id = export
+        .find_snake(snake)
+        .unwrap_or_else(|| usage(&format!("No snake called {} in the game", snake)))
+        .clone();
+
+    let mut game = export.create_game();
+    let reviews = review_game(&export, &mut game, &id, depth);
+    for review in &reviews {
+        let played = review
+            .get_played()
+            .map_or(String::from("-"), |direction| direction.to_string());
+        let marker = if review.is_losing_move() {
+            "  <- played move dies"
+        } else {
+            ""
+        };
+        println!(
+            "turn {}: played {}, now {}, survives: {}{}",
+            review.get_turn(),
+            played,
+            review.get_chosen(),
+            join(review.get_surviving()),
+            marker
+        );
+    }
+
+    match export.get_death(&id) {
+        Some(death) => println!(
+            "eliminated on turn {}: {}",
+            death.get_turn(),
+            death.get_cause()
+        ),
+        None => println!("survived the game"),
+    }
+    match find_divergence(&reviews) {
+        Some(review) => println!(
+            "diverged from survival on turn {}: {} survived",
+            review.get_turn(),
+            join(review.get_surviving())
+        ),
+        None => println!("no turn where a move played died while another survived"),
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::env;
+use std::path::Path;
+use std::process;
+use std::time::Instant;
+
+use curunir::config::Config;
+use curunir::constants::CONFIG_PATH;
+use curunir::requests::logged_move::LoggedMove;
+use curunir::structures::game_state::GameState;
+
+const USAGE: &str = "Usage: replay [options] LOG...
+
+Decides every logged move request again with this build, in order, and prints
+each turn's logged and new move, marking the turns where they differ
+
+Options:
+    --depth N      Deepest level the search goes to (default no limit)
+    --config PATH  Config file for Curunir (default config.json if it exists)";
+
+// Prints the usage and exits with an error
+fn usage(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(1);
+}
+
+fn main() {
+    let mut max_depth = None;
+    let mut config_path = None;
+    let mut paths = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--depth" => {
+                max_depth = Some(
+                    args.next()
+                        .and_then(|value| value.parse().ok())
+                        .unwrap_or_else(|| usage("--depth needs a number")),
+                )
+            }
+            "--config" => {
+                config_path = Some(
+                    args.next()
+                        .unwrap_or_else(|| usage("--config needs a path")),
+                )
+            }
+            "-h" | "--help" => usage("Move log replay"),
+            _ => paths.push(arg),
+        }
+    }
+
+    if paths.is_empty() {
+        usage("A move log is needed");
+    }
+
+    // Curunir is tuned the same way as the server
+    let config = match config_path {
+        Some(path) => Some(path),
+        None if Path::new(CONFIG_PATH).exists() => Some(String::from(CONFIG_PATH)),
+        None => None,
+    };
+    if let Some(path) = config {
+        match Config::load(&path) {
+            Ok(config) => Config::set(config),
+            Err(e) => usage(&format!("Can't load {}: {}", path, e)),
+        }
+    }
+
+    let mut replayed = 0;
+    let mut changed = 0;
+    for path in paths {
+        let moves = LoggedMove::load_log(&path)
+            .unwrap_or_else(|e| usage(&format!("Can't read {}: {}", path, e)));
+        println!("{}", path);
+
+        // Every game remembers its earlier turns like the server does
+        let mut states: HashMap<String, GameState> = HashMap::new();
+        for logged in moves {
+            let (logged_move, logged_time) = (logged.get_move(), logged.get_time());
+            let (input_game, turn, input_board, you) = logged.into_request().into_values();
+            let state = states.entry(input_game.get_id().clone()).or_default();
+
+            let start = Instant::now();
+            let ids = input_board.get_snake_ids(&you);
+            let board = input_board.into_board(you, turn);
+            let mut game = input_game.into_game();
+            if let Some(max_depth) = max_depth {
+                game.set_max_depth(max_depth);
+            }
+            let direction = state.calculate_move(&mut game, board, ids);
+
+            replayed += 1;
+            let marker = if direction != logged_move {
+                changed += 1;
+                "  <- changed"
+            } else {
+                ""
+            };
+            println!(
+                "turn {}: logged {} ({}ms), now {} ({}ms){}",
+                turn,
+                logged_move,
+                logged_time,
+                direction,
+                start.elapsed().as_millis(),
+                marker
+            );
+        }
+    }
+
+    println!("changed: {} of {} moves", changed, replayed);
+}
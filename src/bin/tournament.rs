@@ -0,0 +1,106 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process;
+
+use curunir::structures::tournament::{standings, Tournament};
+
+const USAGE: &str = "Usage: tournament [--output PATH] TOURNAMENT
+
+Plays the seeded games of the tournament file between its engines, then prints
+each engine's results against the others with its Elo and a 95% confidence interval
+
+Options:
+    --output PATH  Writes every game's result to PATH, one JSON object per line";
+
+// Prints the usage and exits with an error
+fn usage(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(1);
+}
+
+fn main() -> io::Result<()> {
+    let mut path = None;
+    let mut output = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => {
+                output = Some(
+                    args.next()
+                        .unwrap_or_else(|| usage("--output needs a path")),
+                )
+            }
+            "-h" | "--help" => usage("Engine tournament"),
+            _ if path.is_none() => path = Some(arg),
+            _ => usage(&format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| usage("A tournament file is needed"));
+    let tournament = Tournament::load(&path)?;
+    let mut output = match output {
+        Some(output) => Some(BufWriter::new(File::create(output)?)),
+        None => None,
+    };
+
+    let games = tournament.get_games();
+    let mut written = Ok(());
+    let records = tournament.run(|record| {
+        println!(
+            "game {}/{}: {}, winner: {}, turns: {}",
+            record.get_game() + 1,
+            games,
+            record.get_engines().join(" vs "),
+            record.get_winner().map_or("none", |winner| winner.as_str()),
+            record.get_turns()
+        );
+        if let Some(output) = &mut output {
+            if written.is_ok() {
+                written = serde_json::to_writer(&mut *output, record)
+                    .map_err(io::Error::from)
+                    .and_then(|_| writeln!(output));
+            }
+        }
+    })?;
+    written?;
+    if let Some(output) = &mut output {
+        output.flush()?;
+    }
+
+    let names: Vec<String> = tournament
+        .get_engines()
+        .iter()
+        .map(|engine| engine.get_name().clone())
+        .collect();
+    let width = names.iter().map(String::len).max().unwrap_or(0).max(6);
+
+    println!(
+        "\n{:width$} {:>7} {:>6} {:>6} {:>6} {:>7} {:>14}",
+        "engine",
+        "matches",
+        "wins",
+        "draws",
+        "losses",
+        "score",
+        "elo",
+        width = width
+    );
+    for standing in standings(&names, &records) {
+        println!(
+            "{:width$} {:>7} {:>6} {:>6} {:>6} {:>6.1}% {:>6.0} ± {:<5.0}",
+            standing.get_name(),
+            standing.get_matches(),
+            standing.get_wins(),
+            standing.get_draws(),
+            standing.get_losses(),
+            standing.get_score() * 100.0,
+            standing.get_elo(),
+            standing.get_elo_margin(),
+            width = width
+        );
+    }
+
+    Ok(())
+}
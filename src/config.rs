@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufReader, ErrorKind};
+use std::sync::OnceLock;
+
+use crate::constants::{LENGTH_ADVANTAGE, MAX_SEARCH, TIME_BUDGET};
+use crate::ruleset::Ruleset;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+// How much each part of an evaluation is worth, scores are compared by their weighted sum
+// The defaults keep every part an order of magnitude above the ones after it
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Weights {
+    competitors: i64,
+    open: i64,
+    weak_snake: i64,
+    length: i64,
+    area: i64,
+    food: i64,
+    safe_directions: i64,
+    // Score of a dead snake, only used when outcomes are averaged
+    death: i64,
+}
+
+impl Default for Weights {
+    fn default() -> Weights {
+        Weights {
+            competitors: -1_000_000_000,
+            open: 100_000_000,
+            weak_snake: 1_000_000,
+            length: 1_000,
+            area: 10,
+            food: 10,
+            safe_directions: 1,
+            death: -10_000_000_000,
+        }
+    }
+}
+
+impl Weights {
+    pub fn get_competitors(&self) -> i64 {
+        self.competitors
+    }
+
+    pub fn get_open(&self) -> i64 {
+        self.open
+    }
+
+    pub fn get_weak_snake(&self) -> i64 {
+        self.weak_snake
+    }
+
+    pub fn get_length(&self) -> i64 {
+        self.length
+    }
+
+    pub fn get_area(&self) -> i64 {
+        self.area
+    }
+
+    pub fn get_food(&self) -> i64 {
+        self.food
+    }
+
+    pub fn get_safe_directions(&self) -> i64 {
+        self.safe_directions
+    }
+
+    pub fn get_death(&self) -> i64 {
+        self.death
+    }
+}
+
+// Settings for one kind of game
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Profile {
+    // How much longer than another snake I have to be to hunt it
+    length_advantage: i32,
+    // Longest route checked when looking for an escape
+    max_search: i32,
+    // Share of the game's timeout spent searching
+    time_budget: f32,
+    weights: Weights,
+}
+
+impl Default for Profile {
+    fn default() -> Profile {
+        Profile {
+            length_advantage: LENGTH_ADVANTAGE,
+            max_search: MAX_SEARCH,
+            time_budget: TIME_BUDGET,
+            weights: Weights::default(),
+        }
+    }
+}
+
+impl Profile {
+    pub fn get_length_advantage(&self) -> i32 {
+        self.length_advantage
+    }
+
+    pub fn get_max_search(&self) -> i32 {
+        self.max_search
+    }
+
+    pub fn get_time_budget(&self) -> f32 {
+        self.time_budget
+    }
+
+    pub fn get_weights(&self) -> &Weights {
+        &self.weights
+    }
+}
+
+// Tuning loaded at startup, so different personalities can run from the same build
+// Missing profiles and settings keep their defaults
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Config {
+    standard: Profile,
+    royale: Profile,
+    duel: Profile,
+    constrictor: Profile,
+}
+
+impl Config {
+    // Reads a config from the JSON file at path
+    pub fn load(path: &str) -> io::Result<Config> {
+        let reader = BufReader::new(File::open(path)?);
+        serde_json::from_reader(reader).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    }
+
+    // Makes config the one returned by Config::get
+    // Panics if a config was already in use, it can only be set once
+    pub fn set(config: Config) {
+        CONFIG
+            .set(config)
+            .expect("the config can only be set once, before it's used");
+    }
+
+    // Returns the config in use, the defaults if none was set
+    pub fn get() -> &'static Config {
+        CONFIG.get_or_init(Config::default)
+    }
+
+    // Returns the profile for a game with ruleset, started by num_snakes snakes
+    pub fn get_profile(&self, ruleset: &Ruleset, num_snakes: usize) -> &Profile {
+        if ruleset.is_constrictor() {
+            &self.constrictor
+        } else if ruleset.is_royale() {
+            &self.royale
+        } else if num_snakes == 2 {
+            &self.duel
+        } else {
+            &self.standard
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{_TEST_PATH, CONFIG_PATH};
+    use crate::load_object;
+
+    #[test]
+    fn test_load() {
+        let config = Config::load(&format!("{}config-01.json", _TEST_PATH)).unwrap();
+
+        assert_eq!(config.duel.length_advantage, 2);
+        assert_eq!(config.duel.weights.food, 50);
+        assert_eq!(config.duel.time_budget, 0.4);
+        // Settings missing from the file keep their defaults
+        assert_eq!(config.duel.max_search, MAX_SEARCH);
+        assert_eq!(config.standard, Profile::default());
+    }
+
+    #[test]
+    fn test_load_default() {
+        let config = Config::load(CONFIG_PATH).unwrap();
+
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_get_profile() {
+        let config = Config::load(&format!("{}config-01.json", _TEST_PATH)).unwrap();
+        let standard = load_object!(Ruleset, "test_board-04", _TEST_PATH);
+        let constrictor = load_object!(Ruleset, "check_area_constrictor-01", _TEST_PATH);
+
+        assert_eq!(config.get_profile(&standard, 2), &config.duel);
+        assert_eq!(config.get_profile(&standard, 4), &config.standard);
+        assert_eq!(config.get_profile(&constrictor, 2), &config.constrictor);
+    }
+}
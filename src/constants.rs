@@ -1,3 +1,5 @@
+use crate::board::simulate::SearchMode;
+
 // Snake details
 pub const API_VERSION: &str = "1";
 pub const AUTHOR: &str = "Kyle Stang";
@@ -7,15 +9,35 @@ pub const TAIL: &str = "bolt";
 
 // Game constants
 pub const DIRECTIONS: usize = 3;
-pub const EXPONENT: i32 = 13;
+pub const HEALTH_BUCKET: i32 = 10;
 pub const LENGTH_ADVANTAGE: i32 = 5;
+pub const MAX_DEPTH: i32 = 30;
 pub const MAX_HEALTH: i32 = 100;
 pub const MAX_SEARCH: i32 = 30;
+pub const MCTS_EXPLORATION: f64 = 1.4;
+// Monte Carlo tree search iterations when the search has no time limit
+pub const MCTS_ITERATIONS: u32 = 2000;
+pub const MCTS_ROLLOUT: i32 = 10;
+pub const SEARCH_MODE: SearchMode = SearchMode::Minimax;
+// Threads in the search pool, 0 uses one per core
+pub const SEARCH_THREADS: usize = 0;
+pub const SPAWN_FOOD: bool = true;
+// Chance of surviving the predicted replies needed to count as alive in the expected search
+pub const SURVIVAL_PROBABILITY: f64 = 0.8;
+pub const TABLE_SIZE: usize = 1 << 16;
+// Default share of the timeout spent searching, set for each kind of game in the config
+pub const TIME_BUDGET: f32 = 0.5;
 pub const YOU_ID: u8 = 0;
+pub const ZOBRIST_SEED: u64 = 0x5EED_CAFE_F00D;
+
+// Config file read at startup, replaced by the first command line argument
+pub const CONFIG_PATH: &str = "config.json";
 
 // Log settings
 pub const LOG_LEVEL: u32 = 0;
 pub const LOG_PATH: &str = "logs/";
+// Appends every move request, with the move and its time, to LOG_PATH/<game id>.jsonl
+pub const LOG_MOVES: bool = false;
 pub const _TEST_PATH: &str = "testdata/";
 
 // Drawing settings
@@ -5,9 +5,11 @@ macro_rules! load_object {
             .read(true)
             .open(format!("{}{}.json", $test_path, $filename))
             .unwrap();
-        let board: crate::move_request::MoveRequest = serde_json::from_reader(file).unwrap();
-        let (_, turn, board, you) = board.into_values();
-        let board = board.into_board(you, turn);
+        let board: $crate::requests::move_request::MoveRequest =
+            serde_json::from_reader(file).unwrap();
+        let (input_game, turn, board, you) = board.into_values();
+        let mut board = board.into_board(you, turn);
+        board.set_wrapped(input_game.into_ruleset().is_wrapped());
         board
     }};
     (Battlesnake, $filename:expr, $test_path:expr) => {{
@@ -15,7 +17,8 @@ macro_rules! load_object {
             .read(true)
             .open(format!("{}{}.json", $test_path, $filename))
             .unwrap();
-        let snake: crate::input_snake::InputSnake = from_reader(file).unwrap();
+        let snake: $crate::requests::input_snake::InputSnake =
+            serde_json::from_reader(file).unwrap();
         let snake = snake.into_battlesnake();
         snake
     }};
@@ -24,7 +27,8 @@ macro_rules! load_object {
             .read(true)
             .open(format!("{}{}.json", $test_path, $filename))
             .unwrap();
-        let board: crate::move_request::MoveRequest = serde_json::from_reader(file).unwrap();
+        let board: $crate::requests::move_request::MoveRequest =
+            serde_json::from_reader(file).unwrap();
         let (input_game, _, _, _) = board.into_values();
         input_game.into_ruleset()
     }};
@@ -1,7 +1,9 @@
+pub mod game_export;
 pub mod index_response;
 pub mod input_board;
 pub mod input_game;
 pub mod input_ruleset;
 pub mod input_snake;
+pub mod logged_move;
 pub mod move_request;
 pub mod move_response;
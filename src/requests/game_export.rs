@@ -0,0 +1,325 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, ErrorKind};
+
+use crate::board::Board;
+use crate::coordinate::Coordinate;
+use crate::direction::Direction;
+use crate::game::Game;
+use crate::input_board::InputBoard;
+use crate::input_snake::InputSnake;
+use crate::ruleset::Ruleset;
+
+fn default_timeout() -> i32 {
+    500
+}
+
+// A finished game as the official engine exports it, the game followed by every frame of it
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct GameExport {
+    game: ExportGame,
+    frames: Vec<ExportFrame>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExportGame {
+    #[serde(rename = "ID")]
+    id: String,
+    width: i32,
+    height: i32,
+    // Every setting is a string, by its name in the engine
+    #[serde(default)]
+    ruleset: HashMap<String, String>,
+    #[serde(default = "default_timeout")]
+    snake_timeout: i32,
+}
+
+// The board at the start of one turn, eliminated snakes stay in it with how they died
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExportFrame {
+    turn: i32,
+    snakes: Vec<ExportSnake>,
+    #[serde(default)]
+    food: Vec<ExportPoint>,
+    #[serde(default)]
+    hazards: Vec<ExportPoint>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExportSnake {
+    #[serde(rename = "ID")]
+    id: String,
+    name: String,
+    body: Vec<ExportPoint>,
+    health: i32,
+    #[serde(default)]
+    death: Option<ExportDeath>,
+    #[serde(default)]
+    latency: String,
+    #[serde(default)]
+    squad: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExportDeath {
+    cause: String,
+    turn: i32,
+    #[serde(default)]
+    eliminated_by: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExportPoint {
+    x: i32,
+    y: i32,
+}
+
+impl ExportPoint {
+    pub fn to_coordinate(self) -> Coordinate {
+        Coordinate::new(self.x, self.y)
+    }
+}
+
+impl ExportDeath {
+    pub fn get_cause(&self) -> &String {
+        &self.cause
+    }
+
+    pub fn get_turn(&self) -> i32 {
+        self.turn
+    }
+
+    pub fn get_eliminated_by(&self) -> &String {
+        &self.eliminated_by
+    }
+}
+
+impl ExportSnake {
+    pub fn get_id(&self) -> &String {
+        &self.id
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_death(&self) -> Option<&ExportDeath> {
+        self.death.as_ref()
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.death.is_none()
+    }
+
+    // Convert self to a snake as the API would send it
+    fn to_input_snake(&self) -> InputSnake {
+        InputSnake::new(
+            self.id.clone(),
+            self.name.clone(),
+            self.health,
+            self.body
+                .iter()
+                .map(|point| point.to_coordinate())
+                .collect(),
+            self.latency.clone(),
+            self.squad.clone(),
+        )
+    }
+}
+
+impl ExportFrame {
+    pub fn get_turn(&self) -> i32 {
+        self.turn
+    }
+
+    pub fn get_snakes(&self) -> &Vec<ExportSnake> {
+        &self.snakes
+    }
+
+    // Returns the snake with id, alive or not
+    pub fn get_snake(&self, id: &str) -> Option<&ExportSnake> {
+        self.snakes.iter().find(|snake| snake.id == id)
+    }
+}
+
+impl GameExport {
+    // Reads a game from the JSON file at path
+    pub fn load(path: &str) -> io::Result<GameExport> {
+        let reader = BufReader::new(File::open(path)?);
+        let export: GameExport = serde_json::from_reader(reader)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+
+        if export.frames.is_empty() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "the game has no frames",
+            ));
+        }
+        Ok(export)
+    }
+
+    pub fn get_id(&self) -> &String {
+        &self.game.id
+    }
+
+    pub fn get_frames(&self) -> &Vec<ExportFrame> {
+        &self.frames
+    }
+
+    // Returns the id of the snake with id or name
+    pub fn find_snake(&self, snake: &str) -> Option<&String> {
+        self.frames[0]
+            .snakes
+            .iter()
+            .find(|other| other.id == snake || other.name == snake)
+            .map(|other| &other.id)
+    }
+
+    // Returns how the snake with id was eliminated, None if it lasted the whole game
+    pub fn get_death(&self, id: &str) -> Option<&ExportDeath> {
+        self.frames
+            .last()
+            .and_then(|frame| frame.get_snake(id))
+            .and_then(ExportSnake::get_death)
+    }
+
+    // Returns the rules of the game, with the engine's defaults for missing settings
+    pub fn get_ruleset(&self) -> Ruleset {
+        let settings = &self.game.ruleset;
+        let number = |name: &str, default: i32| {
+            settings
+                .get(name)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default)
+        };
+        let flag = |name: &str| settings.get(name).is_some_and(|value| value == "true");
+
+        Ruleset::new(
+            settings
+                .get("name")
+                .cloned()
+                .unwrap_or_else(|| String::from("standard")),
+            String::from("export"),
+            number("foodSpawnChance", 15),
+            number("minimumFood", 1),
+            number("damagePerTurn", 14),
+            number("shrinkEveryNTurns", 25),
+            flag("allowBodyCollisions"),
+            flag("sharedElimination"),
+            flag("sharedHealth"),
+            flag("sharedLength"),
+        )
+    }
+
+    // Returns the game as the server would have started it
+    pub fn create_game(&self) -> Game {
+        Game::new(
+            self.game.id.clone(),
+            self.get_ruleset(),
+            self.game.snake_timeout,
+        )
+    }
+
+    // Returns frame as the snake with id saw it and the API id of every snake on the board
+    // None if that snake was already eliminated
+    pub fn get_board(&self, frame: usize, id: &str) -> Option<(Board, Vec<String>)> {
+        let frame = &self.frames[frame];
+        let you = frame.get_snake(id).filter(|you| you.is_alive())?;
+
+        let snakes = frame
+            .snakes
+            .iter()
+            .filter(|snake| snake.is_alive())
+            .map(ExportSnake::to_input_snake)
+            .collect();
+        let points =
+            |points: &Vec<ExportPoint>| points.iter().map(|point| point.to_coordinate()).collect();
+        let input_board = InputBoard::new(
+            self.game.height,
+            self.game.width,
+            points(&frame.food),
+            points(&frame.hazards),
+            snakes,
+        );
+
+        let you = you.to_input_snake();
+        let ids = input_board.get_snake_ids(&you);
+        let mut board = input_board.into_board(you, frame.turn);
+        board.set_wrapped(self.get_ruleset().is_wrapped());
+        Some((board, ids))
+    }
+
+    // Returns the move the snake with id made from frame to the next one
+    pub fn get_move(&self, frame: usize, id: &str) -> Option<Direction> {
+        let (board, _) = self.get_board(frame, id)?;
+        let next = self.frames.get(frame + 1)?.get_snake(id)?;
+        let head = board.get_snakes()[0].get_head();
+        let next_head = next.body.first()?.to_coordinate();
+
+        Direction::ALL
+            .into_iter()
+            .find(|&direction| board.wrap(head.get_neighbour(direction)) == board.wrap(next_head))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{_TEST_PATH, YOU_ID};
+
+    fn load() -> GameExport {
+        GameExport::load(&format!("{}game_export-01.json", _TEST_PATH)).unwrap()
+    }
+
+    #[test]
+    fn test_load() {
+        let export = load();
+
+        assert_eq!(export.get_id(), "game-export-01");
+        assert_eq!(export.get_frames().len(), 3);
+        assert_eq!(export.find_snake("curunir").unwrap(), "gs_me");
+        assert_eq!(export.find_snake("gs_them").unwrap(), "gs_them");
+        assert_eq!(export.get_death("gs_me").unwrap().get_turn(), 2);
+        assert!(export.get_death("gs_them").is_none());
+        assert_eq!(export.get_ruleset().get_food_spawn_chance(), 15);
+    }
+
+    // get_board()
+    #[test]
+    fn test_get_board() {
+        let export = load();
+
+        let (board, ids) = export.get_board(1, "gs_them").unwrap();
+
+        assert_eq!(ids, ["gs_them", "gs_me"]);
+        assert_eq!(board.get_turn(), 1);
+        assert_eq!(board.get_snakes()[0].get_id(), YOU_ID);
+        assert_eq!(board.get_snakes()[0].get_head(), Coordinate::new(5, 6));
+        assert_eq!(board.get_snakes()[1].get_health(), 99);
+        // Eliminated snakes are left off the board
+        assert!(export.get_board(2, "gs_me").is_none());
+        assert_eq!(
+            export.get_board(2, "gs_them").unwrap().0.get_snakes().len(),
+            1
+        );
+    }
+
+    // get_move()
+    #[test]
+    fn test_get_move() {
+        let export = load();
+
+        assert_eq!(export.get_move(0, "gs_me"), Some(Direction::Left));
+        assert_eq!(export.get_move(1, "gs_me"), Some(Direction::Left));
+        assert_eq!(export.get_move(1, "gs_them"), Some(Direction::Left));
+        // Nothing comes after the last frame
+        assert_eq!(export.get_move(2, "gs_them"), None);
+    }
+}
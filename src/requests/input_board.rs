@@ -5,7 +5,7 @@ use crate::constants::YOU_ID;
 use crate::coordinate::Coordinate;
 use crate::input_snake::InputSnake;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct InputBoard {
     height: i32,
     width: i32,
@@ -15,19 +15,67 @@ pub struct InputBoard {
 }
 
 impl InputBoard {
+    pub fn new(
+        height: i32,
+        width: i32,
+        food: Vec<Coordinate>,
+        hazards: Vec<Coordinate>,
+        snakes: Vec<InputSnake>,
+    ) -> InputBoard {
+        InputBoard {
+            height,
+            width,
+            food,
+            hazards,
+            snakes,
+        }
+    }
+
+    // Returns the API id of every snake, indexed by its id on the board from into_board
+    pub fn get_snake_ids(&self, you: &InputSnake) -> Vec<String> {
+        let mut ids = vec![you.get_id().clone()];
+        for snake in self.snakes.iter().rev() {
+            if snake.get_id() != you.get_id() {
+                ids.push(snake.get_id().clone());
+            }
+        }
+        ids
+    }
+
     // Convert self to a Board struct
     pub fn into_board(mut self, you: InputSnake, turn: i32) -> Board {
         let num_snakes = self.snakes.len();
+        // Number squads in order of appearance, snakes outside a squad have none
+        let mut squads: Vec<String> = Vec::new();
+        let mut find_squad = |snake: &InputSnake| {
+            let squad = snake.get_squad();
+            if squad.is_empty() {
+                return None;
+            }
+            match squads.iter().position(|name| name == squad) {
+                Some(i) => Some(i as u8),
+                None => {
+                    squads.push(squad.clone());
+                    Some(squads.len() as u8 - 1)
+                }
+            }
+        };
+
         // Create a vector to store the snakes
         let mut snakes = Vec::with_capacity(num_snakes);
         // Push my snake as a Battlesnake onto the snakes vector
         let you_id = you.get_id().clone();
+        let squad = find_squad(&you);
         snakes.push(you.into_battlesnake(YOU_ID));
+        snakes[0].set_squad(squad);
         // Push all the other snakes as Battlesnakes onto the snakes vector
         let mut snake_id = 1;
         while let Some(snake) = self.snakes.pop() {
             if snake.get_id() != &you_id {
-                snakes.push(snake.into_battlesnake(snake_id));
+                let squad = find_squad(&snake);
+                let mut battlesnake = snake.into_battlesnake(snake_id);
+                battlesnake.set_squad(squad);
+                snakes.push(battlesnake);
                 snake_id += 1;
             }
         }
@@ -4,7 +4,7 @@ use crate::ruleset::Ruleset;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct InputGame {
     id: String,
     ruleset: InputRuleset,
@@ -12,6 +12,10 @@ pub struct InputGame {
 }
 
 impl InputGame {
+    pub fn get_id(&self) -> &String {
+        &self.id
+    }
+
     pub fn into_game(self) -> Game {
         Game::new(self.id, self.ruleset.into_ruleset(), self.timeout)
     }
@@ -1,7 +1,7 @@
 use crate::ruleset::Ruleset;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct InputRuleset {
     name: String,
     version: String,
@@ -37,7 +37,7 @@ impl InputRuleset {
 }
 
 #[allow(non_snake_case)]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct InputSettings {
     foodSpawnChance: i32,
     minimumFood: i32,
@@ -66,7 +66,7 @@ impl InputSettings {
 }
 
 #[allow(non_snake_case)]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct InputRoyale {
     #[allow(non_snake_case)]
     shrinkEveryNTurns: i32,
@@ -79,7 +79,7 @@ impl InputRoyale {
 }
 
 #[allow(non_snake_case)]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct InputSquad {
     allowBodyCollisions: bool,
     sharedElimination: bool,
@@ -4,7 +4,7 @@ use std::collections::VecDeque;
 use crate::battlesnake::Battlesnake;
 use crate::coordinate::Coordinate;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct InputSnake {
     id: String,
     name: String,
@@ -14,13 +14,41 @@ pub struct InputSnake {
     head: Coordinate,
     length: usize,
     shout: String,
+    #[serde(default)]
+    squad: String,
 }
 
 impl InputSnake {
+    // Returns a snake as the API would send it, its head is the first part of body
+    pub fn new(
+        id: String,
+        name: String,
+        health: i32,
+        body: Vec<Coordinate>,
+        latency: String,
+        squad: String,
+    ) -> InputSnake {
+        InputSnake {
+            id,
+            name,
+            health,
+            head: body.first().copied().unwrap_or_default(),
+            length: body.len(),
+            body,
+            latency,
+            shout: String::new(),
+            squad,
+        }
+    }
+
     pub fn get_id(&self) -> &String {
         &self.id
     }
 
+    pub fn get_squad(&self) -> &String {
+        &self.squad
+    }
+
     // Convert self to a Battlesnake struct
     pub fn into_battlesnake(self, id: u8) -> Battlesnake {
         Battlesnake::new(
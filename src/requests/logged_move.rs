@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+
+use crate::constants::LOG_PATH;
+use crate::direction::Direction;
+use crate::move_request::MoveRequest;
+
+// A move request with the move given in answer and how long it took, one per line of a move log
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LoggedMove {
+    request: MoveRequest,
+    r#move: Direction,
+    // Milliseconds from receiving the request to answering it
+    time: u64,
+}
+
+impl LoggedMove {
+    pub fn new(request: MoveRequest, r#move: Direction, time: u64) -> LoggedMove {
+        LoggedMove {
+            request,
+            r#move,
+            time,
+        }
+    }
+
+    // Returns the file the moves of the game with id are logged to
+    pub fn log_path(id: &str) -> String {
+        format!("{}{}.jsonl", LOG_PATH, id)
+    }
+
+    pub fn get_request(&self) -> &MoveRequest {
+        &self.request
+    }
+
+    pub fn get_move(&self) -> Direction {
+        self.r#move
+    }
+
+    pub fn get_time(&self) -> u64 {
+        self.time
+    }
+
+    pub fn into_request(self) -> MoveRequest {
+        self.request
+    }
+
+    // Adds self as a line at the end of the log at path
+    pub fn append(&self, path: &str) -> io::Result<()> {
+        let mut line = serde_json::to_string(self)?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new().append(true).create(true).open(path)?;
+        file.write_all(line.as_bytes())
+    }
+
+    // Reads every move in the log at path, in the order they were made
+    pub fn load_log(path: &str) -> io::Result<Vec<LoggedMove>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut moves = Vec::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let logged = serde_json::from_str(&line).map_err(|e| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("line {}: {}", number + 1, e),
+                )
+            })?;
+            moves.push(logged);
+        }
+        Ok(moves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::_TEST_PATH;
+
+    // load_log()
+    #[test]
+    fn test_load_log() {
+        let moves = LoggedMove::load_log(&format!("{}move_log-01.jsonl", _TEST_PATH)).unwrap();
+
+        assert_eq!(moves.len(), 3);
+        assert_eq!(moves[0].get_move(), Direction::Up);
+        assert_eq!(moves[1].get_move(), Direction::Right);
+        assert_eq!(moves[2].get_time(), 44);
+        assert_eq!(moves[2].get_request().get_game().get_id(), "history-01");
+    }
+
+    // append()
+    #[test]
+    fn test_append() {
+        let moves = LoggedMove::load_log(&format!("{}move_log-01.jsonl", _TEST_PATH)).unwrap();
+        let path = std::env::temp_dir().join(format!("curunir-{}.jsonl", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        for logged in &moves {
+            logged.append(path).unwrap();
+        }
+        let appended = LoggedMove::load_log(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(appended.len(), moves.len());
+        for (appended, logged) in appended.iter().zip(&moves) {
+            assert_eq!(appended.get_move(), logged.get_move());
+            assert_eq!(appended.get_time(), logged.get_time());
+        }
+    }
+}
@@ -4,7 +4,7 @@ use crate::input_board::InputBoard;
 use crate::input_game::InputGame;
 use crate::input_snake::InputSnake;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MoveRequest {
     game: InputGame,
     turn: i32,
@@ -22,6 +22,10 @@ impl MoveRequest {
         }
     }
 
+    pub fn get_game(&self) -> &InputGame {
+        &self.game
+    }
+
     // Break up self and return it's values
     pub fn into_values(self) -> (InputGame, i32, InputBoard, InputSnake) {
         (self.game, self.turn, self.board, self.you)
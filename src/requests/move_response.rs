@@ -1,13 +1,15 @@
 use serde::Serialize;
 
+use crate::direction::Direction;
+
 #[derive(Serialize)]
 pub struct MoveResponse {
-    r#move: String,
+    r#move: Direction,
     shout: String,
 }
 
 impl MoveResponse {
-    pub fn new(r#move: String, shout: String) -> MoveResponse {
+    pub fn new(r#move: Direction, shout: String) -> MoveResponse {
         MoveResponse { r#move, shout }
     }
 }
@@ -1,5 +1,15 @@
+pub mod arena;
 pub mod battlesnake;
 pub mod board;
 pub mod coordinate;
+pub mod decision;
+pub mod direction;
+pub mod evaluation;
 pub mod game;
+pub mod game_state;
+pub mod opponent_model;
+pub mod post_mortem;
+pub mod rng;
 pub mod ruleset;
+pub mod tournament;
+pub mod transposition_table;
@@ -0,0 +1,413 @@
+use rayon::ThreadPoolBuilder;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::battlesnake::Battlesnake;
+use crate::board::royale_shrink::RoyaleShrink;
+use crate::board::simulate::SearchMode;
+use crate::board::spawn_food::FoodSpawn;
+use crate::board::Board;
+use crate::config::Config;
+use crate::constants::{MAX_DEPTH, MAX_HEALTH};
+use crate::coordinate::Coordinate;
+use crate::direction::Direction;
+use crate::game::Game;
+use crate::game_state::GameState;
+use crate::rng::Rng;
+use crate::ruleset::Ruleset;
+
+// Length of every snake at the start of a game
+const START_LENGTH: usize = 3;
+
+// How a copy of Curunir is set up
+#[derive(Clone, Debug, PartialEq)]
+pub struct Engine {
+    search_mode: SearchMode,
+    max_depth: i32,
+    // Searches every level to max_depth without a time limit, so its games can be replayed
+    fixed_depth: bool,
+    // Tuning for the engine, the config in use without one
+    config: Option<Arc<Config>>,
+}
+
+impl Engine {
+    pub fn new(search_mode: SearchMode) -> Engine {
+        Engine {
+            search_mode,
+            max_depth: MAX_DEPTH,
+            fixed_depth: false,
+            config: None,
+        }
+    }
+
+    pub fn get_search_mode(&self) -> SearchMode {
+        self.search_mode
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: i32) {
+        self.max_depth = max_depth;
+    }
+
+    pub fn set_fixed_depth(&mut self, fixed_depth: bool) {
+        self.fixed_depth = fixed_depth;
+    }
+
+    pub fn set_config(&mut self, config: Arc<Config>) {
+        self.config = Some(config);
+    }
+
+    // Returns a game played by this engine
+    fn create_game(&self, id: String, ruleset: Ruleset, timeout: i32) -> Game {
+        let mut game = Game::new(id, ruleset, timeout);
+        game.set_search_mode(self.search_mode);
+        game.set_max_depth(self.max_depth);
+        game.set_fixed_depth(self.fixed_depth);
+        // Threads racing for the transposition table could still change a move, so one thread searches
+        if self.fixed_depth {
+            let thread_pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+            game.set_thread_pool(Arc::new(thread_pool));
+        }
+        if let Some(config) = &self.config {
+            game.set_config(Arc::clone(config));
+        }
+        game
+    }
+}
+
+// Who decides the moves of a snake in the arena
+#[derive(Clone, Debug, PartialEq)]
+pub enum Player {
+    // A copy of this snake
+    Curunir(Engine),
+    // Picks any move that doesn't run into a wall or a body
+    Random,
+    // Heads for the closest food, avoiding walls and bodies
+    Hungry,
+}
+
+impl Player {
+    // Returns the player called name: curunir, paranoid, mcts, expected, random or hungry
+    pub fn from_name(name: &str) -> Option<Player> {
+        match name {
+            "curunir" => Some(Player::Curunir(Engine::new(SearchMode::Minimax))),
+            "paranoid" => Some(Player::Curunir(Engine::new(SearchMode::Paranoid))),
+            "mcts" => Some(Player::Curunir(Engine::new(SearchMode::Mcts))),
+            "expected" => Some(Player::Curunir(Engine::new(SearchMode::Expected))),
+            "random" => Some(Player::Random),
+            "hungry" => Some(Player::Hungry),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Player::Curunir(engine) => match engine.search_mode {
+                SearchMode::Minimax => "curunir",
+                SearchMode::Paranoid => "paranoid",
+                SearchMode::Mcts => "mcts",
+                SearchMode::Expected => "expected",
+            },
+            Player::Random => "random",
+            Player::Hungry => "hungry",
+        }
+    }
+}
+
+// How one game in the arena went
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameResult {
+    // The seat of the last snake standing, None for a draw or a game played alone
+    winner: Option<usize>,
+    turns: i32,
+    // For every turn, the seat and move of each snake still in the game
+    moves: Vec<Vec<(usize, Direction)>>,
+}
+
+impl GameResult {
+    pub fn get_winner(&self) -> Option<usize> {
+        self.winner
+    }
+
+    pub fn get_turns(&self) -> i32 {
+        self.turns
+    }
+
+    pub fn get_moves(&self) -> &Vec<Vec<(usize, Direction)>> {
+        &self.moves
+    }
+}
+
+// Plays full games offline, every snake's moves are decided here instead of by a server
+pub struct Arena {
+    ruleset: Ruleset,
+    width: i32,
+    height: i32,
+    // Milliseconds each copy of Curunir has to answer, like the game timeout
+    timeout: i32,
+    // Games still going after this many turns are a draw
+    max_turns: i32,
+    // Seeds the starting positions, food spawns, royale shrinks and the built-in bots
+    seed: u64,
+}
+
+impl Arena {
+    pub fn new(
+        ruleset: Ruleset,
+        width: i32,
+        height: i32,
+        timeout: i32,
+        max_turns: i32,
+        seed: u64,
+    ) -> Arena {
+        Arena {
+            ruleset,
+            width,
+            height,
+            timeout,
+            max_turns,
+            seed,
+        }
+    }
+
+    // Returns the most snakes a game can start with
+    pub fn max_snakes() -> usize {
+        8
+    }
+
+    // Returns the board at the start of a game for num_snakes snakes, seat i plays the snake with id i
+    // Snakes start on the standard spawn points in a seeded order, each with food next to it
+    pub fn create_board(&self, num_snakes: usize) -> Board {
+        let (right, top) = (self.width - 2, self.height - 2);
+        let (mid_x, mid_y) = (self.width / 2, self.height / 2);
+        let mut spawns = [
+            Coordinate::new(1, 1),
+            Coordinate::new(1, top),
+            Coordinate::new(right, 1),
+            Coordinate::new(right, top),
+            Coordinate::new(1, mid_y),
+            Coordinate::new(mid_x, 1),
+            Coordinate::new(right, mid_y),
+            Coordinate::new(mid_x, top),
+        ];
+
+        // Shuffle which snake gets which spawn point
+        let mut rng = Rng::new(self.seed);
+        for i in (1..spawns.len()).rev() {
+            spawns.swap(i, rng.gen_range(i + 1));
+        }
+
+        let center = Coordinate::new(mid_x, mid_y);
+        let mut food = vec![center];
+        let mut snakes = Vec::with_capacity(num_snakes);
+        for (id, &pos) in spawns.iter().take(num_snakes).enumerate() {
+            let body: VecDeque<Coordinate> = vec![pos; START_LENGTH].into();
+            snakes.push(Battlesnake::new(
+                id as u8,
+                MAX_HEALTH,
+                body,
+                0,
+                pos,
+                START_LENGTH,
+            ));
+
+            // Food goes on the diagonal towards the center
+            let towards = |from: i32, to: i32| from + (to - from).signum();
+            let pos = Coordinate::new(
+                towards(pos.get_x(), center.get_x()),
+                towards(pos.get_y(), center.get_y()),
+            );
+            if !food.contains(&pos) {
+                food.push(pos);
+            }
+        }
+
+        let mut board = Board::new(
+            self.height,
+            self.width,
+            food,
+            Vec::new(),
+            snakes,
+            num_snakes,
+            0,
+        );
+        board.set_wrapped(self.ruleset.is_wrapped());
+        board.set_food_spawn(FoodSpawn::Sampled(Rng::new(self.seed)));
+        board.set_royale_shrink(RoyaleShrink::Sampled(Rng::new(self.seed.wrapping_add(2))));
+        board
+    }
+
+    // Plays a game to the end, seat i is played by players[i]
+    pub fn play(&self, players: &[Player]) -> GameResult {
+        let mut board = self.create_board(players.len());
+        let mut rng = Rng::new(self.seed.wrapping_add(1));
+
+        // Copies of Curunir remember their game like the server does, under the name of their seat
+        let names: Vec<String> = (0..players.len())
+            .map(|seat| format!("seat-{}", seat))
+            .collect();
+        let mut states: Vec<GameState> = players.iter().map(|_| GameState::new()).collect();
+        let mut games: Vec<Option<Game>> = players
+            .iter()
+            .map(|player| match player {
+                Player::Curunir(engine) => Some(engine.create_game(
+                    format!("arena-{}", self.seed),
+                    self.ruleset.clone(),
+                    self.timeout,
+                )),
+                _ => None,
+            })
+            .collect();
+
+        let mut moves = Vec::new();
+        let last_snakes = if players.len() > 1 { 1 } else { 0 };
+        while board.get_snakes().len() > last_snakes && board.get_turn() < self.max_turns {
+            // Every snake decides on the same board
+            let mut turn_moves = Vec::with_capacity(board.get_snakes().len());
+            for snake in board.get_snakes() {
+                let seat = snake.get_id() as usize;
+                let direction = match &mut games[seat] {
+                    Some(game) => {
+                        // The ids of the other snakes change places with mine on my board
+                        let ids = (0..players.len())
+                            .map(|id| match id {
+                                0 => names[seat].clone(),
+                                id if id == seat => names[0].clone(),
+                                id => names[id].clone(),
+                            })
+                            .collect();
+                        let seen = board.from_perspective(seat as u8);
+                        states[seat].calculate_move(game, seen, ids)
+                    }
+                    None => bot_move(&board, snake, &players[seat], &mut rng),
+                };
+                turn_moves.push((seat, direction));
+            }
+
+            for (snake, &(_, direction)) in board.get_snakes_mut().iter_mut().zip(&turn_moves) {
+                let pos = snake.get_move(direction);
+                snake.move_to(pos);
+            }
+            board.game_step(&self.ruleset);
+            moves.push(turn_moves);
+        }
+
+        let winner = match board.get_snakes().as_slice() {
+            [snake] if players.len() > 1 => Some(snake.get_id() as usize),
+            _ => None,
+        };
+
+        GameResult {
+            winner,
+            turns: board.get_turn(),
+            moves,
+        }
+    }
+}
+
+// Returns the move of a built-in bot
+fn bot_move(board: &Board, snake: &Battlesnake, player: &Player, rng: &mut Rng) -> Direction {
+    let options = snake.get_options();
+    let safe: Vec<Direction> = options
+        .into_iter()
+        .filter(|&direction| {
+            let pos = board.wrap(snake.get_move(direction));
+            !board.is_out_of_bounds(pos)
+                && !matches!(board.occupant(pos), Some((_, remaining)) if remaining > 0)
+        })
+        .collect();
+    // Trapped bots still have to move somewhere
+    let choices = if safe.is_empty() {
+        options.to_vec()
+    } else {
+        safe
+    };
+
+    let closest_food = board.find_closest_food(snake.get_head());
+    match (player, closest_food) {
+        (Player::Hungry, Some(food)) => *choices
+            .iter()
+            .min_by_key(|&&direction| board.distance(board.wrap(snake.get_move(direction)), food))
+            .unwrap(),
+        _ => choices[rng.gen_range(choices.len())],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::_TEST_PATH;
+    use crate::load_object;
+
+    fn arena(seed: u64, max_turns: i32) -> Arena {
+        let ruleset = load_object!(Ruleset, "test_board-04", _TEST_PATH);
+        Arena::new(ruleset, 7, 7, 20, max_turns, seed)
+    }
+
+    #[test]
+    fn test_player_names() {
+        for name in [
+            "curunir", "paranoid", "mcts", "expected", "random", "hungry",
+        ] {
+            assert_eq!(Player::from_name(name).unwrap().get_name(), name);
+        }
+        assert_eq!(Player::from_name("nobody"), None);
+    }
+
+    #[test]
+    fn test_create_board() {
+        let board = arena(3, 100).create_board(4);
+
+        assert_eq!(board.get_snakes().len(), 4);
+        for (id, snake) in board.get_snakes().iter().enumerate() {
+            assert_eq!(snake.get_id() as usize, id);
+            assert_eq!(snake.get_length(), START_LENGTH);
+            assert!(!board.is_out_of_bounds(snake.get_head()));
+        }
+        // The center and one for each snake
+        assert_eq!(board.get_food().len(), 5);
+        // Royale games shrink one side at a time, like the real game
+        assert!(matches!(
+            board.get_royale_shrink(),
+            RoyaleShrink::Sampled(_)
+        ));
+    }
+
+    // Games between bots are decided by the seed alone
+    #[test]
+    fn test_play_bots() {
+        let players = [Player::Hungry, Player::Random, Player::Random];
+        let result = arena(7, 200).play(&players);
+
+        assert_eq!(result, arena(7, 200).play(&players));
+        assert_eq!(result.get_moves().len(), result.get_turns() as usize);
+        assert!(result.get_turns() > 0);
+        assert_eq!(result.get_moves()[0].len(), players.len());
+        if let Some(winner) = result.get_winner() {
+            let last_turn = result.get_moves().last().unwrap();
+            assert!(last_turn.iter().any(|&(seat, _)| seat == winner));
+        }
+    }
+
+    // Engines searching at a fixed depth play the same game for the same seed
+    #[test]
+    fn test_play_fixed_depth() {
+        let mut engine = Engine::new(SearchMode::Minimax);
+        engine.set_max_depth(2);
+        engine.set_fixed_depth(true);
+        let players = [Player::Curunir(engine), Player::Random];
+        let result = arena(5, 20).play(&players);
+
+        assert_eq!(result, arena(5, 20).play(&players));
+        assert!(result.get_turns() > 0);
+    }
+
+    #[test]
+    fn test_play_max_turns() {
+        let mut engine = Engine::new(SearchMode::Minimax);
+        engine.set_max_depth(2);
+        let result = arena(1, 5).play(&[Player::Curunir(engine), Player::Hungry]);
+
+        assert!(result.get_turns() <= 5);
+        assert_eq!(result.get_moves().len(), result.get_turns() as usize);
+    }
+}
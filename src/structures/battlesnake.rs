@@ -1,7 +1,8 @@
 use std::collections::VecDeque;
 
-use crate::constants::MAX_HEALTH;
+use crate::constants::{DIRECTIONS, MAX_HEALTH};
 use crate::coordinate::Coordinate;
+use crate::direction::Direction;
 
 // Define the Battlesnake struct
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -12,6 +13,7 @@ pub struct Battlesnake {
     latency: i32,
     head: Coordinate,
     length: usize,
+    squad: Option<u8>,
 }
 
 impl Battlesnake {
@@ -31,6 +33,7 @@ impl Battlesnake {
             latency,
             head,
             length,
+            squad: None,
         }
     }
 
@@ -58,7 +61,7 @@ impl Battlesnake {
         &mut self.body
     }
 
-    pub fn _get_latency(&self) -> i32 {
+    pub fn get_latency(&self) -> i32 {
         self.latency
     }
 
@@ -82,6 +85,19 @@ impl Battlesnake {
         self.length = length;
     }
 
+    pub fn get_squad(&self) -> Option<u8> {
+        self.squad
+    }
+
+    pub fn set_squad(&mut self, squad: Option<u8>) {
+        self.squad = squad;
+    }
+
+    // Returns true if other is a different snake in the same squad
+    pub fn is_ally(&self, other: &Battlesnake) -> bool {
+        self.id != other.get_id() && self.squad.is_some() && self.squad == other.get_squad()
+    }
+
     pub fn get_down(&self) -> Coordinate {
         self.head.get_down()
     }
@@ -107,87 +123,43 @@ impl Battlesnake {
     pub fn eat_food(&mut self) {
         // Reset health to full
         self.health = MAX_HEALTH;
+        self.grow();
+    }
+
+    // Grow self by one tile at the tail
+    pub fn grow(&mut self) {
         // Add piece to back of self
         self.body.push_back(*self.body.back().unwrap());
         // Increase length by 1
         self.length += 1;
     }
 
-    // Return all tiles adjacent to head other than body[1]
-    pub fn get_option(&self, direction: usize) -> Coordinate {
+    // Returns the direction of body[1] from the head
+    // On wrapped boards the neck can be across the edge, more than one tile away
+    fn get_neck_direction(&self) -> Direction {
         let second = self.body[1];
-        let result;
-
-        if second == self.get_down() {
-            result = match direction {
-                0 => self.get_up(),
-                1 => self.get_right(),
-                2 => self.get_left(),
-                _ => panic!("Wrong direction"),
-            };
-        } else if second == self.get_up() {
-            result = match direction {
-                0 => self.get_down(),
-                1 => self.get_right(),
-                2 => self.get_left(),
-                _ => panic!("Wrong direction"),
-            }
-        } else if second == self.get_right() {
-            result = match direction {
-                0 => self.get_down(),
-                1 => self.get_up(),
-                2 => self.get_left(),
-                _ => panic!("Wrong direction"),
-            }
+        let x = second.get_x() - self.head.get_x();
+        let y = second.get_y() - self.head.get_y();
+
+        if y == -1 || y > 1 {
+            Direction::Down
+        } else if y == 1 || y < -1 {
+            Direction::Up
+        } else if x == 1 || x < -1 {
+            Direction::Right
         } else {
-            result = match direction {
-                0 => self.get_down(),
-                1 => self.get_up(),
-                2 => self.get_right(),
-                _ => panic!("Wrong direction"),
-            }
+            Direction::Left
         }
-
-        result
     }
 
-    // Takes a direction without tail and returns absolute direction
-    // Inverse of get_option
-    pub fn get_direction(&self, direction: usize) -> usize {
-        let second = self.body[1];
-        let result;
-
-        if second == self.get_down() {
-            result = match direction {
-                0 => 1,
-                1 => 2,
-                2 => 3,
-                _ => panic!("Wrong direction"),
-            };
-        } else if second == self.get_up() {
-            result = match direction {
-                0 => 0,
-                1 => 2,
-                2 => 3,
-                _ => panic!("Wrong direction"),
-            }
-        } else if second == self.get_right() {
-            result = match direction {
-                0 => 0,
-                1 => 1,
-                2 => 3,
-                _ => panic!("Wrong direction"),
-            }
-        } else {
-            result = match direction {
-                0 => 0,
-                1 => 1,
-                2 => 2,
-                _ => panic!("Wrong direction"),
-            }
-        }
+    // Returns the directions self can move in, every direction other than body[1]
+    pub fn get_options(&self) -> [Direction; DIRECTIONS] {
+        Direction::relative_to(self.get_neck_direction())
+    }
 
-        result
+    // Returns the tile one move from the head in direction
+    pub fn get_move(&self, direction: Direction) -> Coordinate {
+        self.head.get_neighbour(direction)
     }
 
     // Returns true if self lost head-to-head against other
@@ -213,6 +185,7 @@ impl Battlesnake {
 #[cfg(test)]
 mod tests {
     use crate::constants::_TEST_PATH;
+    use crate::direction::Direction;
     use crate::load_object;
 
     // body_collision_with
@@ -247,6 +220,19 @@ mod tests {
         assert_eq!(before_eat, after_eat);
     }
 
+    // get_options
+    #[test]
+    fn test_get_options_wrapped() {
+        let board = load_object!(Board, "wrapped_move-01-after", _TEST_PATH);
+        let snake = &board.get_snakes()[0];
+
+        // The neck is across the right edge, so the options are down, up and left
+        assert_eq!(
+            snake.get_options(),
+            [Direction::Down, Direction::Up, Direction::Left]
+        );
+    }
+
     // lost_head_to_head
     #[test]
     fn test_lose_headon_collision() {
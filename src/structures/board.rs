@@ -2,14 +2,30 @@ pub mod area_controlled;
 pub mod draw;
 pub mod evaluate;
 pub mod game_step;
+pub mod joint_move;
 pub mod longest_path;
+pub mod mcts;
+pub mod occupancy;
+pub mod paranoid;
+pub mod perspective;
+pub mod royale_shrink;
 pub mod simulate;
+pub mod spawn_food;
+pub mod zobrist;
+
+use std::cmp::{max, min};
+use std::time::Instant;
 
 use crate::battlesnake::Battlesnake;
+use crate::board::occupancy::Occupant;
+use crate::board::royale_shrink::RoyaleShrink;
+use crate::board::spawn_food::FoodSpawn;
 use crate::constants::DIRECTIONS;
 use crate::coordinate::Coordinate;
+use crate::direction::Direction;
+use crate::ruleset::Ruleset;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Board {
     height: i32,
     width: i32,
@@ -18,8 +34,41 @@ pub struct Board {
     snakes: Vec<Battlesnake>,
     max_snakes: usize,
     turn: i32,
+    safe_bounds: (Coordinate, Coordinate),
+    wrapped: bool,
+    food_spawn: FoodSpawn,
+    royale_shrink: RoyaleShrink,
+    // When a search of this board has to stop, it runs to the end without one
+    deadline: Option<Instant>,
+    // Per tile, the snake on it and the turn it leaves, for constant time collision checks
+    occupancy: Vec<Occupant>,
+    // The turn the grid was built for, ahead of turn while a move is being made
+    occupancy_turn: i32,
+    occupancy_valid: bool,
+    // Zobrist hash of the position, kept up to date by make_move while hash_valid
+    hash: u64,
+    hash_valid: bool,
+}
+
+// The occupancy grid and hash are derived from the position, and the deadline belongs to the search, so they're left out of comparisons
+impl PartialEq for Board {
+    fn eq(&self, other: &Board) -> bool {
+        self.height == other.height
+            && self.width == other.width
+            && self.food == other.food
+            && self.hazards == other.hazards
+            && self.snakes == other.snakes
+            && self.max_snakes == other.max_snakes
+            && self.turn == other.turn
+            && self.safe_bounds == other.safe_bounds
+            && self.wrapped == other.wrapped
+            && self.food_spawn == other.food_spawn
+            && self.royale_shrink == other.royale_shrink
+    }
 }
 
+impl Eq for Board {}
+
 impl Board {
     pub fn new(
         height: i32,
@@ -30,7 +79,9 @@ impl Board {
         max_snakes: usize,
         turn: i32,
     ) -> Board {
-        Board {
+        let safe_bounds = find_safe_bounds(height, width, &hazards);
+
+        let mut board = Board {
             height,
             width,
             food,
@@ -38,7 +89,20 @@ impl Board {
             snakes,
             max_snakes,
             turn,
-        }
+            safe_bounds,
+            wrapped: false,
+            food_spawn: FoodSpawn::Off,
+            royale_shrink: RoyaleShrink::Projected,
+            deadline: None,
+            occupancy: Vec::new(),
+            occupancy_turn: turn,
+            occupancy_valid: false,
+            hash: 0,
+            hash_valid: false,
+        };
+        board.rebuild_occupancy();
+        board.rebuild_zobrist_hash();
+        board
     }
 
     pub fn get_height(&self) -> i32 {
@@ -54,6 +118,7 @@ impl Board {
     }
 
     pub fn get_food_mut(&mut self) -> &mut Vec<Coordinate> {
+        self.hash_valid = false;
         &mut self.food
     }
 
@@ -61,15 +126,21 @@ impl Board {
         &self.hazards
     }
 
-    pub fn get_hazards_mut(&mut self) -> &mut Vec<Coordinate> {
-        &mut self.hazards
+    pub fn set_hazards(&mut self, mut hazards: Vec<Coordinate>) {
+        hazards.sort_unstable();
+        self.safe_bounds = find_safe_bounds(self.height, self.width, &hazards);
+        self.hazards = hazards;
+        self.hash_valid = false;
     }
 
     pub fn get_snakes(&self) -> &Vec<Battlesnake> {
         &self.snakes
     }
 
+    // Snakes changed through here are searched for until the next game_step rebuilds the grid
     pub fn get_snakes_mut(&mut self) -> &mut Vec<Battlesnake> {
+        self.occupancy_valid = false;
+        self.hash_valid = false;
         &mut self.snakes
     }
 
@@ -81,21 +152,65 @@ impl Board {
         self.max_snakes = max_snakes;
     }
 
+    pub fn is_wrapped(&self) -> bool {
+        self.wrapped
+    }
+
+    pub fn set_wrapped(&mut self, wrapped: bool) {
+        self.wrapped = wrapped;
+    }
+
+    pub fn get_food_spawn(&self) -> FoodSpawn {
+        self.food_spawn
+    }
+
+    pub fn set_food_spawn(&mut self, food_spawn: FoodSpawn) {
+        self.food_spawn = food_spawn;
+    }
+
+    pub fn get_royale_shrink(&self) -> RoyaleShrink {
+        self.royale_shrink
+    }
+
+    pub fn set_royale_shrink(&mut self, royale_shrink: RoyaleShrink) {
+        self.royale_shrink = royale_shrink;
+    }
+
+    pub fn get_deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
     pub fn get_turn(&self) -> i32 {
         self.turn
     }
 
     pub fn set_turn(&mut self, turn: i32) {
         self.turn = turn;
+        self.hash_valid = false;
     }
 
     pub fn body_collision(&self, pos: Coordinate) -> bool {
-        for snake in &self.snakes {
-            if snake.get_body().contains(&pos) {
-                return true;
-            }
+        self.occupant(pos).is_some()
+    }
+
+    // Returns the number of moves from pos to other, crossing the edges on wrapped boards
+    pub fn distance(&self, pos: Coordinate, other: Coordinate) -> i32 {
+        if self.wrapped {
+            let x = (pos.get_x() - other.get_x()).abs();
+            let y = (pos.get_y() - other.get_y()).abs();
+            min(x, self.width - x) + min(y, self.height - y)
+        } else {
+            pos.distance_to(other)
         }
-        false
+    }
+
+    // Returns the tiles adjacent to pos in order: Down, up, right, left
+    pub fn get_adjacent(&self, pos: Coordinate) -> [Coordinate; DIRECTIONS + 1] {
+        pos.get_adjacent().map(|tile| self.wrap(tile))
     }
 
     // Returns the closest food to pos
@@ -103,10 +218,10 @@ impl Board {
         // If food exists
         if !self.food.is_empty() {
             let mut closest_food = self.food[0];
-            let mut closest_distance = pos.distance_to(closest_food);
+            let mut closest_distance = self.distance(pos, closest_food);
             // Iterate over food
             for &food in &self.food[1..] {
-                let current_distance = pos.distance_to(food);
+                let current_distance = self.distance(pos, food);
                 if current_distance < closest_distance {
                     closest_distance = current_distance;
                     closest_food = food;
@@ -131,9 +246,9 @@ impl Board {
 
         // Iterate through all snakes
         for snake in &self.snakes {
-            // Check if snake is short enough, closer, and not the same as current_snake
-            if snake.get_id() != current_snake.get_id() {
-                let current_distance = pos.distance_to(snake.get_head());
+            // Check if snake is short enough, closer, and not the same as current_snake or an ally
+            if snake.get_id() != current_snake.get_id() && !snake.is_ally(current_snake) {
+                let current_distance = self.distance(pos, snake.get_head());
                 if snake.get_length() as i32 <= current_snake.get_length() as i32 - advantage
                     && current_distance < closest_distance
                 {
@@ -147,28 +262,77 @@ impl Board {
         closest_head
     }
 
+    // Returns the number of snakes competing with snake, including itself
+    // Squad-mates aren't competitors
+    pub fn count_competitors(&self, snake: &Battlesnake) -> usize {
+        self.snakes
+            .iter()
+            .filter(|&other| !snake.is_ally(other))
+            .count()
+    }
+
     // Returns the snake with id snake_id, or None
     pub fn get_snake(&self, snake_id: u8) -> Option<&Battlesnake> {
-        for snake in &self.snakes {
-            if snake.get_id() == snake_id {
-                return Some(snake);
-            }
-        }
-        None
+        self.snakes.iter().find(|snake| snake.get_id() == snake_id)
     }
 
     pub fn increment_turn(&mut self) {
         self.turn += 1;
+        self.hash_valid = false;
     }
 
-    // Returns true if pos is against the board walls
+    // Returns true if pos is against the board walls, wrapped boards have no walls
     pub fn is_against_wall(&self, pos: Coordinate) -> bool {
-        pos.get_x() == 0
-            || pos.get_x() == self.width - 1
-            || pos.get_y() == 0
-            || pos.get_y() == self.height - 1
+        !self.wrapped
+            && (pos.get_x() == 0
+                || pos.get_x() == self.width - 1
+                || pos.get_y() == 0
+                || pos.get_y() == self.height - 1)
+    }
+
+    // Returns the number of hazards stacked on pos
+    pub fn count_hazards(&self, pos: Coordinate) -> i32 {
+        // Hazards are kept sorted, so equal positions are adjacent
+        let start = self.hazards.partition_point(|&hazard| hazard < pos);
+        let end = self.hazards.partition_point(|&hazard| hazard <= pos);
+        (end - start) as i32
+    }
+
+    // Returns true if pos is covered by a hazard
+    pub fn is_hazard(&self, pos: Coordinate) -> bool {
+        self.hazards.binary_search(&pos).is_ok()
+    }
+
+    // Returns true if pos is, or may become, a hazard within turns turns in a royale game
+    // Every shrink is assumed to take all four edges, like RoyaleShrink::Projected
+    pub fn is_future_hazard(&self, pos: Coordinate, ruleset: &Ruleset, turns: i32) -> bool {
+        if self.is_hazard(pos) {
+            return true;
+        }
+
+        let shrink_every_n_turns = ruleset.get_shrink_every_n_turns();
+        if !ruleset.is_royale() || shrink_every_n_turns <= 0 {
+            return false;
+        }
+
+        // Number of shrinks needed before the edge reaches pos
+        let (bottom_left, top_right) = self.safe_bounds;
+        let layer = min(
+            min(
+                pos.get_x() - bottom_left.get_x(),
+                top_right.get_x() - pos.get_x(),
+            ),
+            min(
+                pos.get_y() - bottom_left.get_y(),
+                top_right.get_y() - pos.get_y(),
+            ),
+        );
+
+        let next_shrink = (self.turn / shrink_every_n_turns + 1) * shrink_every_n_turns;
+        next_shrink + layer * shrink_every_n_turns <= self.turn + turns
     }
 
+    // Positions on wrapped boards are passed through wrap first, so they're never out of bounds
     pub fn is_out_of_bounds(&self, pos: Coordinate) -> bool {
         pos.get_x() < 0
             || pos.get_x() >= self.width
@@ -176,22 +340,33 @@ impl Board {
             || pos.get_y() >= self.height
     }
 
+    // Returns pos moved back onto a wrapped board, other boards are unchanged
+    pub fn wrap(&self, pos: Coordinate) -> Coordinate {
+        if self.wrapped {
+            Coordinate::new(
+                pos.get_x().rem_euclid(self.width),
+                pos.get_y().rem_euclid(self.height),
+            )
+        } else {
+            pos
+        }
+    }
+
     pub fn open_directions(&self, snake: &Battlesnake) -> i32 {
         let mut options = DIRECTIONS as i32 + 1;
         let pos = snake.get_head();
 
-        for snake in &self.snakes {
-            for &tile in snake.get_body().range(..snake.get_length() - 1) {
-                if tile == pos.get_down()
-                    || tile == pos.get_up()
-                    || tile == pos.get_left()
-                    || tile == pos.get_right()
-                {
-                    options -= 1;
-                }
+        // Tails move out of the way, so only tiles occupied for another turn are blocked
+        for tile in self.get_adjacent(pos) {
+            if matches!(self.occupant(tile), Some((_, remaining)) if remaining > 0) {
+                options -= 1;
             }
         }
 
+        if self.wrapped {
+            return options;
+        }
+
         if pos.get_x() == 0 {
             options -= 1;
         }
@@ -207,6 +382,82 @@ impl Board {
 
         options
     }
+
+    // Turns one edge of the safe area into hazards
+    pub fn shrink(&mut self, side: Direction) {
+        let (bottom_left, top_right) = self.safe_bounds;
+
+        let (start, end) = match side {
+            Direction::Down => (
+                bottom_left,
+                Coordinate::new(top_right.get_x(), bottom_left.get_y()),
+            ),
+            Direction::Up => (
+                Coordinate::new(bottom_left.get_x(), top_right.get_y()),
+                top_right,
+            ),
+            Direction::Right => (
+                Coordinate::new(top_right.get_x(), bottom_left.get_y()),
+                top_right,
+            ),
+            Direction::Left => (
+                bottom_left,
+                Coordinate::new(bottom_left.get_x(), top_right.get_y()),
+            ),
+        };
+
+        let mut hazards = self.hazards.clone();
+        for x in start.get_x()..=end.get_x() {
+            for y in start.get_y()..=end.get_y() {
+                let pos = Coordinate::new(x, y);
+                if !self.is_hazard(pos) {
+                    hazards.push(pos);
+                }
+            }
+        }
+
+        self.set_hazards(hazards);
+    }
+
+    // Returns the number of open directions that don't lead into a damaging hazard
+    pub fn safe_directions(&self, snake: &Battlesnake, ruleset: &Ruleset) -> i32 {
+        let mut options = self.open_directions(snake);
+
+        if ruleset.get_hazard_damage_per_turn() > 0 {
+            for pos in self.get_adjacent(snake.get_head()) {
+                // Tiles that are already blocked were counted by open_directions
+                let blocked = matches!(self.occupant(pos), Some((_, remaining)) if remaining > 0);
+
+                if self.is_hazard(pos)
+                    && !blocked
+                    && !self.is_out_of_bounds(pos)
+                    && !self.food.contains(&pos)
+                {
+                    options -= 1;
+                }
+            }
+        }
+
+        options
+    }
+}
+
+// Smallest rectangle containing every tile without a hazard, as (bottom left, top right)
+fn find_safe_bounds(height: i32, width: i32, hazards: &[Coordinate]) -> (Coordinate, Coordinate) {
+    let mut bottom_left = Coordinate::new(width, height);
+    let mut top_right = Coordinate::new(-1, -1);
+
+    for x in 0..width {
+        for y in 0..height {
+            if hazards.binary_search(&Coordinate::new(x, y)).is_err() {
+                bottom_left =
+                    Coordinate::new(min(bottom_left.get_x(), x), min(bottom_left.get_y(), y));
+                top_right = Coordinate::new(max(top_right.get_x(), x), max(top_right.get_y(), y));
+            }
+        }
+    }
+
+    (bottom_left, top_right)
 }
 
 #[cfg(test)]
@@ -242,6 +493,16 @@ mod tests {
         assert_eq!(food.unwrap(), Coordinate::new(0, 3));
     }
 
+    // distance()
+    #[test]
+    fn test_distance_wrapped() {
+        let board = load_object!(Board, "check_area_wrapped-01", _TEST_PATH);
+
+        let distance = board.distance(Coordinate::new(0, 3), Coordinate::new(6, 5));
+
+        assert_eq!(distance, 3);
+    }
+
     // find_weaker_snake()
     #[test]
     fn test_find_weaker_snake_none() {
@@ -263,6 +524,16 @@ mod tests {
         assert_eq!(snake_head.unwrap(), board.snakes[1].get_head());
     }
 
+    #[test]
+    fn test_find_weaker_snake_squad() {
+        let board = load_object!(Board, "find_weaker_snake_squad-01", _TEST_PATH);
+        let snake = &board.get_snakes()[0];
+
+        let snake_head = board.find_weaker_snake(snake, 5);
+
+        assert!(snake_head.is_none());
+    }
+
     // get_snake()
     #[test]
     fn test_get_snake_none() {
@@ -292,6 +563,15 @@ mod tests {
         assert!(!result);
     }
 
+    #[test]
+    fn test_is_against_wall_wrapped() {
+        let board = load_object!(Board, "check_area_wrapped-01", _TEST_PATH);
+
+        let result = board.is_against_wall(Coordinate::new(0, 3));
+
+        assert!(!result);
+    }
+
     #[test]
     fn test_is_against_wall_true() {
         let board = load_object!(Board, "against_wall_true-01", _TEST_PATH);
@@ -301,6 +581,25 @@ mod tests {
         assert!(result);
     }
 
+    // is_future_hazard()
+    #[test]
+    fn test_is_future_hazard_edge() {
+        let board = load_object!(Board, "check_area_royale-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_royale-01", _TEST_PATH);
+
+        assert!(!board.is_future_hazard(Coordinate::new(0, 1), &ruleset, 0));
+        assert!(board.is_future_hazard(Coordinate::new(0, 1), &ruleset, 1));
+    }
+
+    #[test]
+    fn test_is_future_hazard_center() {
+        let board = load_object!(Board, "check_area_royale-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_royale-01", _TEST_PATH);
+
+        assert!(!board.is_future_hazard(Coordinate::new(1, 1), &ruleset, 25));
+        assert!(board.is_future_hazard(Coordinate::new(1, 1), &ruleset, 26));
+    }
+
     // open_directions()
     #[test]
     fn test_open_directions_2() {
@@ -310,6 +609,14 @@ mod tests {
         assert_eq!(board.open_directions(snake), 2);
     }
 
+    #[test]
+    fn test_open_directions_wrapped() {
+        let board = load_object!(Board, "check_area_wrapped-01", _TEST_PATH);
+        let snake = &board.get_snakes()[0];
+
+        assert_eq!(board.open_directions(snake), 2);
+    }
+
     #[test]
     fn test_open_directions_3() {
         let board = load_object!(Board, "open_directions_3-01", _TEST_PATH);
@@ -2,10 +2,12 @@ use std::collections::VecDeque;
 
 use crate::board::Board;
 use crate::constants::{DIRECTIONS, YOU_ID};
+use crate::coordinate::Coordinate;
+use crate::direction::Direction;
 use crate::ruleset::Ruleset;
 
 impl Board {
-    pub fn area_controlled(&self) -> Vec<i32> {
+    pub fn area_controlled(&self, ruleset: &Ruleset) -> Vec<i32> {
         #[derive(Clone, Copy, PartialEq)]
         enum TileStatus {
             Empty,
@@ -16,6 +18,10 @@ impl Board {
         // Initialization
         let mut areas = vec![0; self.max_snakes];
 
+        // Damaging hazards can be passed through, but don't count as controlled area
+        let hazard_damage = ruleset.get_hazard_damage_per_turn() > 0;
+        let counts = |pos: Coordinate| !(hazard_damage && self.is_hazard(pos));
+
         let mut queue = VecDeque::with_capacity(self.height as usize);
 
         // pos(x,y) = grid[board.get_width() * y + x]
@@ -36,8 +42,8 @@ impl Board {
             if grid[(self.width * current_pos.get_y() + current_pos.get_x()) as usize]
                 != TileStatus::Gone
             {
-                for &pos in current_pos
-                    .get_adjacent()
+                for &pos in self
+                    .get_adjacent(current_pos)
                     .iter()
                     .filter(|&&pos| !self.is_out_of_bounds(pos))
                 {
@@ -47,7 +53,9 @@ impl Board {
                         TileStatus::Empty => {
                             grid[grid_value] = TileStatus::Taken(current_snake_id);
                             queue.push_back((current_snake_id, pos));
-                            areas[current_snake_id as usize] += 1;
+                            if counts(pos) {
+                                areas[current_snake_id as usize] += 1;
+                            }
                         }
                         TileStatus::Gone => (),
                         TileStatus::Taken(other_snake_id) => {
@@ -56,7 +64,9 @@ impl Board {
                                     == self.get_snake(other_snake_id).unwrap().get_length()
                             {
                                 grid[grid_value] = TileStatus::Gone;
-                                areas[other_snake_id as usize] -= 1;
+                                if counts(pos) {
+                                    areas[other_snake_id as usize] -= 1;
+                                }
                             }
                         }
                     }
@@ -66,38 +76,38 @@ impl Board {
         areas
     }
 
-    pub fn calculate_areas(&self, ruleset: &Ruleset) -> [i32; 4] {
+    // Returns the minimum area I control after moving each direction, indexed by Direction
+    pub fn calculate_areas(&self, ruleset: &Ruleset) -> [i32; DIRECTIONS + 1] {
         let mut areas = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
 
         let num_snakes = self.snakes.len();
         let iterations = DIRECTIONS.pow(num_snakes as u32);
 
+        let mut board = self.clone();
+        let mut moves = Vec::with_capacity(num_snakes);
+
         for i in 0..iterations {
-            let mut new_board = self.clone();
-            let mut direction = 0;
+            let mut direction = Direction::Down;
 
+            moves.clear();
             for j in 0..num_snakes {
-                let snake = &mut new_board.get_snakes_mut()[j];
+                let option = (i / DIRECTIONS.pow(j as u32)) % DIRECTIONS;
+                let snake = &board.get_snakes()[j];
+                let snake_direction = snake.get_options()[option];
                 if snake.get_id() == YOU_ID {
-                    direction = snake.get_direction((i / DIRECTIONS.pow(j as u32)) % DIRECTIONS);
+                    direction = snake_direction;
                 }
-                let pos = snake.get_option((i / DIRECTIONS.pow(j as u32)) % DIRECTIONS);
-                snake.move_to(pos);
+                moves.push(snake_direction);
             }
 
-            new_board.game_step(ruleset);
+            let undo = board.make_move(ruleset, &moves);
+            let area = board.area_controlled(ruleset)[YOU_ID as usize];
+            board.unmake_move(undo);
 
-            let area = new_board.area_controlled()[YOU_ID as usize];
-
-            areas[direction].push(area);
+            areas[direction.index()].push(area);
         }
 
-        [
-            *areas[0].iter().min().unwrap_or(&0),
-            *areas[1].iter().min().unwrap_or(&0),
-            *areas[2].iter().min().unwrap_or(&0),
-            *areas[3].iter().min().unwrap_or(&0),
-        ]
+        areas.map(|direction_areas| *direction_areas.iter().min().unwrap_or(&0))
     }
 }
 
@@ -109,12 +119,13 @@ mod tests {
     #[test]
     fn test_area_controlled_one() {
         let mut board = load_object!(Board, "simple-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "simple-01", _TEST_PATH);
 
         board
             .get_snakes_mut()
             .sort_unstable_by_key(|snake| 0 - snake.get_length() as i32);
 
-        let result = board.area_controlled();
+        let result = board.area_controlled(&ruleset);
         let mut correct = Vec::new();
         correct.insert(0, 47);
         assert_eq!(result, correct)
@@ -123,25 +134,37 @@ mod tests {
     #[test]
     fn test_area_controlled_two() {
         let mut board = load_object!(Board, "simple-02", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "simple-02", _TEST_PATH);
         board
             .get_snakes_mut()
             .sort_unstable_by_key(|snake| 0 - snake.get_length() as i32);
 
-        let result = board.area_controlled();
+        let result = board.area_controlled(&ruleset);
         let mut correct = Vec::new();
         correct.insert(0, 19);
         correct.insert(1, 19);
         assert_eq!(result, correct)
     }
 
+    #[test]
+    fn test_area_controlled_hazard() {
+        let board = load_object!(Board, "check_area_hazard-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_hazard-01", _TEST_PATH);
+
+        let result = board.area_controlled(&ruleset);
+
+        assert_eq!(result, vec![18]);
+    }
+
     #[test]
     fn test_area_controlled_three() {
         let mut board = load_object!(Board, "test_board-04", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "test_board-04", _TEST_PATH);
         board
             .get_snakes_mut()
             .sort_unstable_by_key(|snake| 0 - snake.get_length() as i32);
 
-        let result = board.area_controlled();
+        let result = board.area_controlled(&ruleset);
         let mut correct = Vec::new();
         correct.insert(0, 32);
         correct.insert(1, 39);
@@ -1,5 +1,6 @@
 use crate::board::Board;
 use crate::constants::{DRAW_PATH, EYE_RATIO, FOOD_RATIO, PUPIL_RATIO, TILE_SIZE};
+use crate::coordinate::Coordinate;
 
 use image::{ImageResult, Rgb, RgbImage};
 
@@ -12,6 +13,13 @@ impl Board {
 
         for x in 0..self.width as u32 {
             for y in 0..self.height as u32 {
+                // Shade hazard tiles
+                let shade = if self.is_hazard(Coordinate::new(x as i32, y as i32)) {
+                    180
+                } else {
+                    255
+                };
+
                 // Fill in grid
                 for tile_x in 0..TILE_SIZE {
                     for tile_y in 0..TILE_SIZE {
@@ -24,9 +32,9 @@ impl Board {
                             g = 0;
                             r = 0;
                         } else {
-                            b = 255;
-                            g = 255;
-                            r = 255;
+                            b = shade;
+                            g = shade;
+                            r = shade;
                         }
 
                         let x_pixel = x * TILE_SIZE + tile_x;
@@ -66,13 +74,10 @@ impl Board {
             let g2: u8 = (((snake.get_id() as u32 + 176) * 150) % 255) as u8;
             let b2: u8 = (((snake.get_id() as u32 + 95) * 210) % 255) as u8;
 
-            for tile in snake.get_body() {
-                if !(tile.get_x() < 0
-                    || tile.get_x() > self.width - 1
-                    || tile.get_y() < 0
-                    || tile.get_y() > self.height - 1)
-                {
-                    if *tile == snake.get_head() {
+            for &tile in snake.get_body() {
+                let tile = self.wrap(tile);
+                if !self.is_out_of_bounds(tile) {
+                    if tile == self.wrap(snake.get_head()) {
                         let eye_radius = TILE_SIZE as f32 / EYE_RATIO;
                         let pupil_radius = TILE_SIZE as f32 / PUPIL_RATIO;
 
@@ -86,13 +91,9 @@ impl Board {
                                 let y_pixel = imgy - (tile.get_y() as u32 * TILE_SIZE + tile_y) - 1;
 
                                 if radius > eye_radius {
-                                    img.put_pixel(
-                                        x_pixel as u32,
-                                        y_pixel as u32,
-                                        Rgb([r1, g1, b1]),
-                                    );
+                                    img.put_pixel(x_pixel, y_pixel, Rgb([r1, g1, b1]));
                                 } else if radius <= pupil_radius {
-                                    img.put_pixel(x_pixel as u32, y_pixel as u32, Rgb([r2, g2, b2]))
+                                    img.put_pixel(x_pixel, y_pixel, Rgb([r2, g2, b2]))
                                 }
                             }
                         }
@@ -101,7 +102,7 @@ impl Board {
                             for tile_y in 1..TILE_SIZE {
                                 let x_pixel = tile.get_x() as u32 * TILE_SIZE + tile_x;
                                 let y_pixel = imgy - (tile.get_y() as u32 * TILE_SIZE + tile_y) - 1;
-                                img.put_pixel(x_pixel as u32, y_pixel as u32, Rgb([r1, g1, b1]));
+                                img.put_pixel(x_pixel, y_pixel, Rgb([r1, g1, b1]));
                             }
                         }
                     }
@@ -126,4 +127,14 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_draw_wrapped() {
+        let filename = "wrapped_move-01-before";
+        let board = load_object!(Board, filename, _TEST_PATH);
+
+        let result = board.draw(String::from(filename));
+
+        assert!(result.is_ok());
+    }
 }
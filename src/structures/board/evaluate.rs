@@ -1,44 +1,58 @@
 use crate::board::Board;
-use crate::constants::LENGTH_ADVANTAGE;
-use std::cmp::{max, min};
+use crate::config::Profile;
+use crate::evaluation::Evaluation;
+use crate::ruleset::Ruleset;
+use std::cmp::max;
 
 impl Board {
-    pub fn evaluate(&self) -> Vec<u64> {
-        let mut result = vec![0; self.max_snakes];
+    // Scores every snake on the board, indexed by id, eliminated snakes get the default score
+    // Scores are weighted by profile, the profile for this kind of game
+    pub fn evaluate(&self, ruleset: &Ruleset, profile: &Profile) -> Vec<Evaluation> {
+        let mut result = vec![Evaluation::default(); self.max_snakes];
+
+        // Constrictor boards have no food, so area is scored in its place
+        let areas = if ruleset.is_constrictor() {
+            self.area_controlled(ruleset)
+        } else {
+            Vec::new()
+        };
 
         for snake in &self.snakes {
-            let mut score: u64 = 0;
-
-            // digit 0
-            let open_directions = self.open_directions(snake) as u64;
-            score += open_directions;
-
-            // digits 1,2
-            let closest_food = self.find_closest_food(snake.get_head());
-            if let Some(food_pos) = closest_food {
-                let value = max(0, 100 - snake.get_head().distance_to(food_pos));
-                score += 10 * value as u64;
+            // Directions into damaging hazards aren't counted
+            let safe_directions = self.safe_directions(snake, ruleset);
+            let open = self.open_directions(snake) >= 2;
+
+            let mut area = 0;
+            let mut food = 0;
+            if ruleset.is_constrictor() {
+                area = max(0, areas[snake.get_id() as usize]);
+            } else if let Some(food_pos) = self.find_closest_food(snake.get_head()) {
+                food = max(0, 100 - self.distance(snake.get_head(), food_pos));
             }
 
-            // digits 3,4,5
-            score += 1_000 * min(999, snake.get_length() as u64);
-
-            // digits 6,7
-            let weak_head = self.find_weaker_snake(snake, LENGTH_ADVANTAGE);
-            if let Some(head_pos) = weak_head {
-                let value = max(0, 100 - snake.get_head().distance_to(head_pos));
-                score += 1_000_000 * value as u64;
-            }
+            let weak_snake = match self.find_weaker_snake(snake, profile.get_length_advantage()) {
+                Some(head_pos) => max(0, 100 - self.distance(snake.get_head(), head_pos)),
+                None => 0,
+            };
+
+            result[snake.get_id() as usize] = Evaluation::new(
+                self.count_competitors(snake),
+                open,
+                weak_snake,
+                snake.get_length(),
+                food,
+                area,
+                safe_directions,
+            )
+            .weighted(profile.get_weights());
+        }
 
-            // digit 8
-            if open_directions >= 2 {
-                score += 100_000_000
+        // Squad-mates share the best score of their squad
+        for snake in &self.snakes {
+            for ally in self.snakes.iter().filter(|&other| snake.is_ally(other)) {
+                let id = snake.get_id() as usize;
+                result[id] = max(result[id], result[ally.get_id() as usize]);
             }
-
-            // digits 9, 10
-            score += 1_000_000_000 * max(0, 100 - self.snakes.len()) as u64;
-
-            result[snake.get_id() as usize] = score;
         }
 
         result
@@ -47,6 +61,7 @@ impl Board {
 
 #[cfg(test)]
 mod tests {
+    use crate::config::Profile;
     use crate::constants::_TEST_PATH;
     use crate::load_object;
 
@@ -54,41 +69,95 @@ mod tests {
     #[test]
     fn test_compare_to_advantage() {
         let better_board = load_object!(Board, "compare_to_advantage-01-better", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "compare_to_advantage-01-better", _TEST_PATH);
         let worse_board = load_object!(Board, "compare_to_advantage-01-worse", _TEST_PATH);
 
-        assert!(better_board.evaluate()[0] > worse_board.evaluate()[0]);
+        assert!(
+            better_board.evaluate(&ruleset, &Profile::default())[0]
+                > worse_board.evaluate(&ruleset, &Profile::default())[0]
+        );
     }
 
     #[test]
     fn test_compare_to_alive() {
         let better_board = load_object!(Board, "better_than_alive-01-dead", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "better_than_alive-01-dead", _TEST_PATH);
         let worse_board = load_object!(Board, "better_than_alive-01-alive", _TEST_PATH);
 
-        assert!(better_board.evaluate()[0] > worse_board.evaluate()[0]);
+        assert!(
+            better_board.evaluate(&ruleset, &Profile::default())[0]
+                > worse_board.evaluate(&ruleset, &Profile::default())[0]
+        );
     }
 
     #[test]
     fn test_compare_to_dead() {
         let better_board = load_object!(Board, "better_than_dead-01-alive", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "better_than_dead-01-alive", _TEST_PATH);
         let mut worse_board = load_object!(Board, "better_than_dead-01-dead", _TEST_PATH);
         worse_board.max_snakes = 2;
 
-        assert!(better_board.evaluate()[1] > worse_board.evaluate()[1]);
+        assert!(
+            better_board.evaluate(&ruleset, &Profile::default())[1]
+                > worse_board.evaluate(&ruleset, &Profile::default())[1]
+        );
+    }
+
+    #[test]
+    fn test_better_than_constrictor() {
+        let better_board = load_object!(Board, "better_than_constrictor-01-open", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "better_than_constrictor-01-open", _TEST_PATH);
+        let worse_board = load_object!(Board, "better_than_constrictor-01-closed", _TEST_PATH);
+
+        assert!(
+            better_board.evaluate(&ruleset, &Profile::default())[0]
+                > worse_board.evaluate(&ruleset, &Profile::default())[0]
+        );
     }
 
     #[test]
     fn test_better_than_food() {
         let better_board = load_object!(Board, "better_than_food-01-close", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "better_than_food-01-close", _TEST_PATH);
         let worse_board = load_object!(Board, "better_than_food-01-far", _TEST_PATH);
 
-        assert!(better_board.evaluate()[0] > worse_board.evaluate()[0]);
+        assert!(
+            better_board.evaluate(&ruleset, &Profile::default())[0]
+                > worse_board.evaluate(&ruleset, &Profile::default())[0]
+        );
+    }
+
+    #[test]
+    fn test_better_than_hazard() {
+        let better_board = load_object!(Board, "better_than_hazard-01-safe", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "better_than_hazard-01-safe", _TEST_PATH);
+        let worse_board = load_object!(Board, "better_than_hazard-01-hazard", _TEST_PATH);
+
+        assert!(
+            better_board.evaluate(&ruleset, &Profile::default())[0]
+                > worse_board.evaluate(&ruleset, &Profile::default())[0]
+        );
+    }
+
+    #[test]
+    fn test_squad_shared_score() {
+        let board = load_object!(Board, "find_weaker_snake_squad-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "find_weaker_snake_squad-01", _TEST_PATH);
+
+        let result = board.evaluate(&ruleset, &Profile::default());
+
+        assert_eq!(result[0], result[1]);
     }
 
     #[test]
     fn test_better_than_long() {
         let better_board = load_object!(Board, "better_than_long-01-long", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "better_than_long-01-long", _TEST_PATH);
         let worse_board = load_object!(Board, "better_than_long-01-short", _TEST_PATH);
 
-        assert!(better_board.evaluate()[0] > worse_board.evaluate()[0]);
+        assert!(
+            better_board.evaluate(&ruleset, &Profile::default())[0]
+                > worse_board.evaluate(&ruleset, &Profile::default())[0]
+        );
     }
 }
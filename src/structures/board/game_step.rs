@@ -1,8 +1,278 @@
+use std::mem;
+
+use crate::battlesnake::Battlesnake;
+use crate::board::occupancy::Occupant;
+use crate::board::royale_shrink::RoyaleShrink;
+use crate::board::spawn_food::FoodSpawn;
+use crate::board::zobrist::ZobristKeys;
 use crate::board::Board;
+use crate::coordinate::Coordinate;
+use crate::direction::Direction;
 use crate::ruleset::Ruleset;
 
+// Why a snake was removed from the board, following the official rules
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EliminationCause {
+    // Ran its health down to zero
+    OutOfHealth,
+    // Ran out of health from hazard damage
+    Hazard,
+    // Moved off the edge of the board
+    OutOfBounds,
+    // Moved into its own body
+    SelfCollision,
+    // Moved into the body of the snake with this id
+    Collision(u8),
+    // Lost a head-on collision against the snake with this id
+    HeadToHead(u8),
+    // A squad-mate was eliminated with shared elimination on
+    Squad,
+}
+
+// A snake before it moved
+struct SnakeUndo {
+    id: u8,
+    head: Coordinate,
+    tail: Coordinate,
+    health: i32,
+    length: usize,
+    body_length: usize,
+    // What the occupancy grid held where the head moved
+    occupant: Option<Occupant>,
+}
+
+// Everything make_move changed, so unmake_move can put the board back
+pub struct Undo {
+    // Every snake before the move, in board order
+    snakes: Vec<SnakeUndo>,
+    eliminated: Vec<Battlesnake>,
+    // Index and position of each eaten food, in the order it was removed
+    eaten: Vec<(usize, Coordinate)>,
+    spawned: usize,
+    cleared_food: Vec<Coordinate>,
+    hazards: Option<(Vec<Coordinate>, (Coordinate, Coordinate))>,
+    food_spawn: FoodSpawn,
+    royale_shrink: RoyaleShrink,
+    // A snake got longer, so its body can't be updated in place
+    grown: bool,
+    // The occupancy grid was rebuilt instead of updated in place
+    rebuilt: bool,
+    hash: u64,
+}
+
+impl Undo {
+    fn new(food_spawn: FoodSpawn, royale_shrink: RoyaleShrink) -> Undo {
+        Undo {
+            snakes: Vec::new(),
+            eliminated: Vec::new(),
+            eaten: Vec::new(),
+            spawned: 0,
+            cleared_food: Vec::new(),
+            hazards: None,
+            food_spawn,
+            royale_shrink,
+            grown: false,
+            rebuilt: false,
+            hash: 0,
+        }
+    }
+}
+
 impl Board {
-    pub fn game_step(&mut self, ruleset: &Ruleset) {
+    // Moves every snake in its direction and steps the game, without copying the board
+    // moves[i] is the direction of snakes[i], returns the record to undo the turn with
+    pub fn make_move(&mut self, ruleset: &Ruleset, moves: &[Direction]) -> Undo {
+        let mut undo = Undo::new(self.food_spawn, self.royale_shrink);
+        if !self.hash_valid {
+            self.rebuild_zobrist_hash();
+        }
+        undo.hash = self.hash;
+        undo.snakes = self
+            .snakes
+            .iter()
+            .map(|snake| SnakeUndo {
+                id: snake.get_id(),
+                head: snake.get_head(),
+                tail: *snake.get_body().back().unwrap(),
+                health: snake.get_health(),
+                length: snake.get_length(),
+                body_length: snake.get_body().len(),
+                occupant: None,
+            })
+            .collect();
+
+        if self.occupancy_valid {
+            self.advance_occupancy();
+        }
+        for (i, &direction) in moves.iter().enumerate() {
+            let pos = self.wrap(self.snakes[i].get_move(direction));
+            undo.snakes[i].occupant = self.move_snake(i, pos);
+        }
+
+        self.step(ruleset, &mut undo);
+        self.update_zobrist_hash(&undo);
+        undo
+    }
+
+    // Puts the board back to how it was before make_move
+    pub fn unmake_move(&mut self, undo: Undo) {
+        self.turn -= 1;
+        self.food_spawn = undo.food_spawn;
+        self.royale_shrink = undo.royale_shrink;
+
+        if let Some((hazards, safe_bounds)) = undo.hazards {
+            self.hazards = hazards;
+            self.safe_bounds = safe_bounds;
+        }
+
+        // Undo the food changes in reverse order
+        if !undo.cleared_food.is_empty() {
+            self.food = undo.cleared_food;
+        }
+        self.food.truncate(self.food.len() - undo.spawned);
+        for &(i, pos) in undo.eaten.iter().rev() {
+            if i == self.food.len() {
+                self.food.push(pos);
+            } else {
+                let moved = self.food[i];
+                self.food.push(moved);
+                self.food[i] = pos;
+            }
+        }
+
+        // Bring back eliminated snakes and restore the original order
+        self.snakes.extend(undo.eliminated);
+        let order = &undo.snakes;
+        self.snakes.sort_unstable_by_key(|snake| {
+            order
+                .iter()
+                .position(|before| before.id == snake.get_id())
+                .unwrap()
+        });
+
+        // Clear the new heads from the grid, last snake first in case heads share a tile
+        if !undo.rebuilt {
+            for i in (0..self.snakes.len()).rev() {
+                if let Some(occupant) = order[i].occupant {
+                    self.restore_occupant(self.snakes[i].get_head(), occupant);
+                }
+            }
+        }
+
+        for (snake, before) in self.snakes.iter_mut().zip(order) {
+            let body = snake.get_body_mut();
+            body.pop_front();
+            body.truncate(before.body_length - 1);
+            body.push_back(before.tail);
+            snake.set_head(before.head);
+            snake.set_health(before.health);
+            snake.set_length(before.length);
+        }
+
+        if undo.rebuilt {
+            self.rebuild_occupancy();
+        } else {
+            self.reset_occupancy_turn();
+        }
+        self.hash = undo.hash;
+        self.hash_valid = true;
+    }
+
+    // Brings the hash from before make_move up to date with the turn recorded in undo
+    // Like the occupancy grid, it's only updated in place when every snake just moved a tile
+    fn update_zobrist_hash(&mut self, undo: &Undo) {
+        if !undo.eliminated.is_empty()
+            || undo.grown
+            || undo.hazards.is_some()
+            || !undo.cleared_food.is_empty()
+        {
+            self.rebuild_zobrist_hash();
+            return;
+        }
+
+        let keys = ZobristKeys::get();
+        let mut hash = undo.hash ^ keys.turn(self.turn - 1) ^ keys.turn(self.turn);
+
+        // The old head is now the neck and the tail left its tile, the rest of the body keeps its keys
+        for (snake, before) in self.snakes.iter().zip(&undo.snakes) {
+            let id = snake.get_id();
+            hash ^= keys.head(id, before.head) ^ keys.head(id, snake.get_head());
+            hash ^= keys.body(id, before.head, self.turn - 1);
+            hash ^= keys.body(id, before.tail, self.turn - before.body_length as i32);
+            hash ^= keys.health(id, before.health) ^ keys.health(id, snake.get_health());
+        }
+
+        // Without a snake growing no food was eaten, so any new food is at the end
+        for &pos in &self.food[self.food.len() - undo.spawned..] {
+            hash ^= keys.food(pos);
+        }
+
+        self.hash = hash;
+        self.hash_valid = true;
+    }
+
+    /*
+    Resolve one turn after every snake has moved, in the official order:
+        Move snakes and reduce their health (done by move_to)
+        Damage snakes in hazards
+        Feed snakes
+        Spawn food
+        Eliminate snakes
+    Returns the id and cause of every snake eliminated this turn
+    */
+    pub fn game_step(&mut self, ruleset: &Ruleset) -> Vec<(u8, EliminationCause)> {
+        // Snakes were moved outside make_move, so the grid and hash can't be trusted
+        self.invalidate_occupancy();
+        self.invalidate_zobrist_hash();
+        self.step(ruleset, &mut Undo::new(self.food_spawn, self.royale_shrink))
+    }
+
+    // game_step, recording what changed in undo
+    fn step(&mut self, ruleset: &Ruleset, undo: &mut Undo) -> Vec<(u8, EliminationCause)> {
+        let mut eliminations = Vec::new();
+
+        // Remember each snake's squad to find squads that lose members
+        let starting_squads: Vec<Option<u8>> = if ruleset.is_squad() {
+            self.snakes.iter().map(|snake| snake.get_squad()).collect()
+        } else {
+            Vec::new()
+        };
+
+        // Snakes that moved off the edge of a wrapped board come back on the other side
+        if self.wrapped {
+            for i in 0..self.snakes.len() {
+                let head = self.wrap(self.snakes[i].get_head());
+                let snake = &mut self.snakes[i];
+                snake.set_head(head);
+                snake.get_body_mut()[0] = head;
+            }
+        }
+
+        // Damage snakes in hazards, food in a hazard cancels the damage
+        let hazard_damage = ruleset.get_hazard_damage_per_turn();
+        if hazard_damage > 0 && !self.hazards.is_empty() {
+            let mut i = 0;
+
+            while i < self.snakes.len() {
+                let head = self.snakes[i].get_head();
+                let damage = hazard_damage * self.count_hazards(head);
+
+                if damage > 0 && !self.food.contains(&head) {
+                    let health = self.snakes[i].get_health() - damage;
+
+                    // Eliminate snakes that ran out of health in a hazard
+                    if health <= 0 {
+                        eliminations.push((self.snakes[i].get_id(), EliminationCause::Hazard));
+                        undo.eliminated.push(self.snakes.remove(i));
+                        continue;
+                    }
+                    self.snakes[i].set_health(health);
+                }
+
+                i += 1;
+            }
+        }
+
         // Check all food
         let mut i = 0;
 
@@ -19,12 +289,16 @@ impl Board {
 
             // Remove food if eaten
             if food_eaten {
-                self.food.swap_remove(i);
+                undo.eaten.push((i, self.food.swap_remove(i)));
             } else {
                 i += 1;
             }
         }
 
+        let food = self.food.len();
+        self.spawn_food(ruleset);
+        undo.spawned = self.food.len() - food;
+
         /*
         Any Battlesnake that has been eliminated is removed from the game board:
             Health less than or equal to 0
@@ -39,39 +313,154 @@ impl Board {
 
         while i < self.snakes.len() {
             let snake = &self.snakes[i];
-            let x = snake.get_head().get_x();
-            let y = snake.get_head().get_y();
 
-            if snake.get_health() < ruleset.get_minimum_food()
-                || (x < 0 || x > self.width - 1 || y < 0 || y > self.height - 1)
-            {
-                self.snakes.remove(i);
+            let cause = if snake.get_health() <= 0 {
+                Some(EliminationCause::OutOfHealth)
+            } else if self.is_out_of_bounds(snake.get_head()) {
+                Some(EliminationCause::OutOfBounds)
+            } else {
+                None
+            };
+
+            if let Some(cause) = cause {
+                eliminations.push((snake.get_id(), cause));
+                undo.eliminated.push(self.snakes.remove(i));
             } else {
                 i += 1;
             }
         }
 
-        // Check for collisions
-        let mut to_remove = Vec::with_capacity(self.snakes.len());
+        // Check for collisions, squads may be allowed to pass through each other
+        let allow_body_collisions = ruleset.is_squad() && ruleset.get_allow_body_collisions();
+        let collision_start = eliminations.len();
         for snake in &self.snakes {
-            for other_snake in &self.snakes {
-                if snake.lost_headon(other_snake) || snake.body_collision_with(other_snake) {
-                    to_remove.push(snake.get_id());
-                    break;
-                }
+            let body_collision = self.snakes.iter().find(|other_snake| {
+                other_snake.get_id() != snake.get_id()
+                    && snake.body_collision_with(other_snake)
+                    && !(allow_body_collisions && snake.is_ally(other_snake))
+            });
+
+            let cause = if snake.body_collision_with(snake) {
+                Some(EliminationCause::SelfCollision)
+            } else if let Some(other_snake) = body_collision {
+                Some(EliminationCause::Collision(other_snake.get_id()))
+            } else {
+                self.snakes
+                    .iter()
+                    .find(|other_snake| snake.lost_headon(other_snake))
+                    .map(|other_snake| EliminationCause::HeadToHead(other_snake.get_id()))
+            };
+
+            if let Some(cause) = cause {
+                eliminations.push((snake.get_id(), cause));
             }
         }
 
-        self.get_snakes_mut()
-            .retain(|snake| !to_remove.contains(&snake.get_id()));
+        let collided = &eliminations[collision_start..];
+        self.remove_snakes(undo, |snake| {
+            collided.iter().any(|&(id, _)| id == snake.get_id())
+        });
+
+        if ruleset.is_squad() {
+            self.share_squad_attributes(ruleset, starting_squads, &mut eliminations, undo);
+        }
+
+        // Constrictor snakes grow every turn and never lose health, so food is never needed
+        if ruleset.is_constrictor() {
+            for snake in &mut self.snakes {
+                snake.eat_food();
+            }
+            undo.cleared_food = mem::take(&mut self.food);
+        }
 
         self.increment_turn();
+
+        // Shrink the safe area of royale games, onto every edge while searching
+        if self.is_shrink_turn(ruleset) {
+            undo.hazards = Some((self.hazards.clone(), self.safe_bounds));
+            self.shrink_royale();
+        }
+
+        // Heads were added to the grid as the snakes moved, anything else needs a rebuild
+        undo.grown = self
+            .snakes
+            .iter()
+            .zip(&undo.snakes)
+            .any(|(snake, before)| snake.get_body().len() != before.body_length);
+        if !self.occupancy_valid || !undo.eliminated.is_empty() || undo.grown {
+            self.rebuild_occupancy();
+            undo.rebuilt = true;
+        }
+
+        eliminations
+    }
+
+    // Removes every eliminated snake, keeping them in undo
+    fn remove_snakes(&mut self, undo: &mut Undo, eliminated: impl Fn(&Battlesnake) -> bool) {
+        let mut i = 0;
+
+        while i < self.snakes.len() {
+            if eliminated(&self.snakes[i]) {
+                undo.eliminated.push(self.snakes.remove(i));
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    // Apply the shared health, length and elimination rules to every squad
+    fn share_squad_attributes(
+        &mut self,
+        ruleset: &Ruleset,
+        mut starting_squads: Vec<Option<u8>>,
+        eliminations: &mut Vec<(u8, EliminationCause)>,
+        undo: &mut Undo,
+    ) {
+        // Squads with a member eliminated this turn
+        for snake in &self.snakes {
+            if let Some(i) = starting_squads
+                .iter()
+                .position(|&squad| squad == snake.get_squad())
+            {
+                starting_squads.swap_remove(i);
+            }
+        }
+
+        if ruleset.get_shared_elimination() {
+            let lost_member = |snake: &Battlesnake| {
+                snake.get_squad().is_some() && starting_squads.contains(&snake.get_squad())
+            };
+            for snake in self.snakes.iter().filter(|&snake| lost_member(snake)) {
+                eliminations.push((snake.get_id(), EliminationCause::Squad));
+            }
+            self.remove_snakes(undo, lost_member);
+        }
+
+        for i in 0..self.snakes.len() {
+            let snake = &self.snakes[i];
+            let allies = self.snakes.iter().filter(|&other| snake.is_ally(other));
+            let max_health = allies.clone().map(|ally| ally.get_health()).max();
+            let max_length = allies.map(|ally| ally.get_length()).max();
+
+            let snake = &mut self.snakes[i];
+            if let Some(health) = max_health.filter(|_| ruleset.get_shared_health()) {
+                snake.set_health(snake.get_health().max(health));
+            }
+            if let Some(length) = max_length.filter(|_| ruleset.get_shared_length()) {
+                while snake.get_length() < length {
+                    snake.grow();
+                }
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::constants::_TEST_PATH;
+    use super::EliminationCause;
+    use crate::board::spawn_food::FoodSpawn;
+    use crate::constants::{_TEST_PATH, DIRECTIONS};
+    use crate::direction::Direction;
     use crate::load_object;
 
     // game_step()
@@ -82,9 +471,21 @@ mod tests {
         let ruleset = load_object!(Ruleset, "body_collision-01-before", _TEST_PATH);
         after_collision.set_max_snakes(2);
 
-        before_collision.game_step(&ruleset);
+        let eliminations = before_collision.game_step(&ruleset);
 
         assert_eq!(before_collision, after_collision);
+        assert_eq!(eliminations, vec![(1, EliminationCause::Collision(0))]);
+    }
+
+    #[test]
+    fn test_constrictor_move() {
+        let mut before = load_object!(Board, "constrictor_move-01-before", _TEST_PATH);
+        let after = load_object!(Board, "constrictor_move-01-after", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "constrictor_move-01-before", _TEST_PATH);
+
+        before.game_step(&ruleset);
+
+        assert_eq!(before, after);
     }
 
     #[test]
@@ -96,9 +497,16 @@ mod tests {
         let ruleset = load_object!(Ruleset, "double_headon_collision-01-before", _TEST_PATH);
         after_collision.set_max_snakes(3);
 
-        before_collision.game_step(&ruleset);
+        let eliminations = before_collision.game_step(&ruleset);
 
         assert_eq!(before_collision, after_collision);
+        assert_eq!(
+            eliminations,
+            vec![
+                (1, EliminationCause::HeadToHead(2)),
+                (2, EliminationCause::HeadToHead(1))
+            ]
+        );
     }
 
     #[test]
@@ -112,6 +520,41 @@ mod tests {
         assert_eq!(before_eat, after_eat);
     }
 
+    #[test]
+    fn test_hazard_damage() {
+        let mut before = load_object!(Board, "hazard_damage-01-before", _TEST_PATH);
+        let after = load_object!(Board, "hazard_damage-01-after", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "hazard_damage-01-before", _TEST_PATH);
+
+        before.game_step(&ruleset);
+
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_hazard_elimination() {
+        let mut before = load_object!(Board, "hazard_elimination-01-before", _TEST_PATH);
+        let mut after = load_object!(Board, "hazard_elimination-01-after", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "hazard_elimination-01-before", _TEST_PATH);
+        after.set_max_snakes(2);
+
+        let eliminations = before.game_step(&ruleset);
+
+        assert_eq!(before, after);
+        assert_eq!(eliminations, vec![(1, EliminationCause::Hazard)]);
+    }
+
+    #[test]
+    fn test_hazard_food() {
+        let mut before = load_object!(Board, "hazard_food-01-before", _TEST_PATH);
+        let after = load_object!(Board, "hazard_food-01-after", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "hazard_food-01-before", _TEST_PATH);
+
+        before.game_step(&ruleset);
+
+        assert_eq!(before, after);
+    }
+
     #[test]
     fn test_headon_collision() {
         let mut before_collision = load_object!(Board, "headon_collision-01-before", _TEST_PATH);
@@ -119,9 +562,10 @@ mod tests {
         let ruleset = load_object!(Ruleset, "headon_collision-01-before", _TEST_PATH);
         after_collision.set_max_snakes(2);
 
-        before_collision.game_step(&ruleset);
+        let eliminations = before_collision.game_step(&ruleset);
 
         assert_eq!(before_collision, after_collision);
+        assert_eq!(eliminations, vec![(1, EliminationCause::HeadToHead(0))]);
     }
 
     #[test]
@@ -131,9 +575,10 @@ mod tests {
         let ruleset = load_object!(Ruleset, "out_of_bounds-01-before", _TEST_PATH);
         after.set_max_snakes(2);
 
-        before.game_step(&ruleset);
+        let eliminations = before.game_step(&ruleset);
 
         assert_eq!(before, after);
+        assert_eq!(eliminations, vec![(1, EliminationCause::OutOfBounds)]);
     }
 
     #[test]
@@ -143,6 +588,83 @@ mod tests {
         let ruleset = load_object!(Ruleset, "out_of_health-01-before", _TEST_PATH);
         after.set_max_snakes(2);
 
+        let eliminations = before.game_step(&ruleset);
+
+        assert_eq!(before, after);
+        assert_eq!(eliminations, vec![(1, EliminationCause::OutOfHealth)]);
+    }
+
+    #[test]
+    fn test_royale_shrink() {
+        let mut before = load_object!(Board, "royale_shrink-01-before", _TEST_PATH);
+        let after = load_object!(Board, "royale_shrink-01-after", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "royale_shrink-01-before", _TEST_PATH);
+
+        before.game_step(&ruleset);
+
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_squad_collision() {
+        let mut before = load_object!(Board, "squad_collision-01-before", _TEST_PATH);
+        let after = load_object!(Board, "squad_collision-01-after", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "squad_collision-01-before", _TEST_PATH);
+
+        before.game_step(&ruleset);
+
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_squad_elimination() {
+        let mut before = load_object!(Board, "squad_elimination-01-before", _TEST_PATH);
+        let mut after = load_object!(Board, "squad_elimination-01-after", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "squad_elimination-01-before", _TEST_PATH);
+        after.set_max_snakes(3);
+
+        let eliminations = before.game_step(&ruleset);
+
+        assert_eq!(before, after);
+        assert_eq!(
+            eliminations,
+            vec![
+                (2, EliminationCause::OutOfBounds),
+                (1, EliminationCause::Squad)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_squad_share() {
+        let mut before = load_object!(Board, "squad_share-01-before", _TEST_PATH);
+        let after = load_object!(Board, "squad_share-01-after", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "squad_share-01-before", _TEST_PATH);
+
+        before.game_step(&ruleset);
+
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_starvation() {
+        let mut before = load_object!(Board, "starvation-01-before", _TEST_PATH);
+        let mut after = load_object!(Board, "starvation-01-after", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "starvation-01-before", _TEST_PATH);
+        after.set_max_snakes(3);
+
+        let eliminations = before.game_step(&ruleset);
+
+        assert_eq!(before, after);
+        assert_eq!(eliminations, vec![(2, EliminationCause::OutOfHealth)]);
+    }
+
+    #[test]
+    fn test_wrapped_move() {
+        let mut before = load_object!(Board, "wrapped_move-01-before", _TEST_PATH);
+        let after = load_object!(Board, "wrapped_move-01-after", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "wrapped_move-01-before", _TEST_PATH);
+
         before.game_step(&ruleset);
 
         assert_eq!(before, after);
@@ -154,8 +676,67 @@ mod tests {
         let after = load_object!(Board, "no_change-01-after", _TEST_PATH);
         let ruleset = load_object!(Ruleset, "no_change-01-before", _TEST_PATH);
 
-        before.game_step(&ruleset);
+        let eliminations = before.game_step(&ruleset);
 
         assert_eq!(before, after);
+        assert_eq!(eliminations, vec![]);
+    }
+
+    // make_move() and unmake_move()
+    #[test]
+    fn test_make_unmake_move() {
+        let fixtures = [
+            "test_board-03",
+            "body_collision-01-before",
+            "constrictor_move-01-before",
+            "eat-01-before",
+            "hazard_elimination-01-before",
+            "royale_shrink-01-before",
+            "squad_elimination-01-before",
+            "wrapped_move-01-before",
+        ];
+
+        for name in fixtures {
+            let mut board = load_object!(Board, name, _TEST_PATH);
+            let ruleset = load_object!(Ruleset, name, _TEST_PATH);
+            board.set_food_spawn(FoodSpawn::Expected);
+            let num_snakes = board.get_snakes().len() as u32;
+
+            // Every joint move gives the same board as game_step, and is undone exactly
+            // The hash kept by make_move matches hashing the stepped board from scratch
+            for i in 0..DIRECTIONS.pow(num_snakes) {
+                let moves: Vec<Direction> = board
+                    .get_snakes()
+                    .iter()
+                    .enumerate()
+                    .map(|(j, snake)| {
+                        snake.get_options()[(i / DIRECTIONS.pow(j as u32)) % DIRECTIONS]
+                    })
+                    .collect();
+
+                let mut stepped = board.clone();
+                for (j, &direction) in moves.iter().enumerate() {
+                    let pos = stepped.wrap(stepped.get_snakes()[j].get_move(direction));
+                    stepped.get_snakes_mut()[j].move_to(pos);
+                }
+                stepped.game_step(&ruleset);
+
+                let mut made = board.clone();
+                let undo = made.make_move(&ruleset, &moves);
+                assert_eq!(made, stepped, "{} move {}", name, i);
+                assert!(made.is_zobrist_hash_valid());
+                assert_eq!(
+                    made.zobrist_hash(),
+                    stepped.zobrist_hash(),
+                    "{} move {}",
+                    name,
+                    i
+                );
+
+                made.unmake_move(undo);
+                assert_eq!(made, board, "{} move {}", name, i);
+                assert_eq!(made.zobrist_hash(), board.zobrist_hash());
+            }
+        }
     }
 }
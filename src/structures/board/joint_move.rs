@@ -0,0 +1,83 @@
+use crate::board::Board;
+use crate::direction::Direction;
+
+impl Board {
+    // Returns the move each snake on previous made to reach self, indexed by its id on previous
+    // Snakes are matched by their neck, since ids shift when a snake is eliminated
+    // Eliminated snakes have no move, and no moves are found unless self is the turn after previous
+    pub fn find_joint_move(&self, previous: &Board) -> Vec<Option<Direction>> {
+        let mut moves = vec![None; previous.max_snakes];
+        if self.turn != previous.turn + 1 {
+            return moves;
+        }
+
+        for old in &previous.snakes {
+            let head = old.get_head();
+            let snake = self
+                .snakes
+                .iter()
+                .find(|snake| snake.get_body().get(1) == Some(&head));
+
+            // Heads can only cross an edge on a wrapped board
+            if let Some(snake) = snake {
+                moves[old.get_id() as usize] = Direction::ALL.into_iter().find(|&direction| {
+                    self.wrap(head.get_neighbour(direction)) == snake.get_head()
+                });
+            }
+        }
+
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::_TEST_PATH;
+    use crate::direction::Direction;
+    use crate::load_object;
+    use crate::move_request::MoveRequest;
+
+    // find_joint_move()
+    #[test]
+    fn test_find_joint_move() {
+        let load = |name| {
+            let (_, turn, board, you) = load_object!(MoveRequest, name, _TEST_PATH).into_values();
+            board.into_board(you, turn)
+        };
+        let turn1 = load("history-01-turn1");
+        let turn2 = load("history-01-turn2");
+        let turn3 = load("history-01-turn3");
+
+        // Me, then the other snakes in reverse order of the request
+        assert_eq!(
+            turn2.find_joint_move(&turn1),
+            [
+                Some(Direction::Up),
+                Some(Direction::Down),
+                Some(Direction::Left)
+            ]
+        );
+        // The second snake was eliminated, so the third now has id 1
+        assert_eq!(
+            turn3.find_joint_move(&turn2),
+            [Some(Direction::Right), None, Some(Direction::Down)]
+        );
+        // Turns were missed
+        assert_eq!(turn3.find_joint_move(&turn1), [None, None, None]);
+    }
+
+    #[test]
+    fn test_find_joint_move_wrapped() {
+        let mut board = load_object!(Board, "check_area_wrapped-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_wrapped-01", _TEST_PATH);
+        let previous = board.clone();
+
+        // Both snakes cross an edge
+        board.make_move(&ruleset, &[Direction::Left, Direction::Down]);
+
+        assert_eq!(
+            board.find_joint_move(&previous),
+            [Some(Direction::Left), Some(Direction::Down)]
+        );
+    }
+}
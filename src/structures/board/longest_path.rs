@@ -1,16 +1,24 @@
+use std::cmp::max;
+
 use crate::board::Board;
-use crate::constants::YOU_ID;
+use crate::constants::{MAX_HEALTH, YOU_ID};
 use crate::coordinate::Coordinate;
+use crate::ruleset::Ruleset;
 
 impl Board {
     // Find the longest possible route a snake can travel from the current position
+    // health is what the snake has before moving to pos
+    // visited marks the tiles of the current route, it needs one entry per tile and is left cleared
+    #[allow(clippy::too_many_arguments)]
     pub fn longest_path(
         &self,
+        ruleset: &Ruleset,
         pos: Coordinate,
         mut current_area: i32,
         max_area: i32,
-        gone: &mut Vec<Coordinate>,
+        visited: &mut [bool],
         mut food_eaten: usize,
+        mut health: i32,
     ) -> i32 {
         // Reached end of search, return
         if current_area >= max_area {
@@ -23,54 +31,76 @@ impl Board {
         }
 
         // Check if tile has already been visited
-        if gone.contains(&pos) {
+        let tile = self.tile_index(pos);
+        if visited[tile] {
             return current_area;
         }
 
-        // Increment food counter
+        // Increment food counter, eating restores health and skips hazard damage
         if self.food.contains(&pos) {
             food_eaten += 1;
+            health = MAX_HEALTH;
+        } else {
+            // Tiles that may shrink into the hazard by the time they're reached count as hazards
+            let hazards = if self.is_future_hazard(pos, ruleset, current_area + 1) {
+                max(1, self.count_hazards(pos))
+            } else {
+                0
+            };
+            health -= 1 + ruleset.get_hazard_damage_per_turn() * hazards;
+        }
+
+        // Check if the snake starves or is killed by a hazard on this tile
+        if health <= 0 {
+            return current_area;
         }
 
         // Check for snake collisions, return max_area if I can tail chase
-        for snake in &self.snakes {
-            let body = snake.get_body();
-            // Iterate over snake body
-            for (i, tile) in body.iter().enumerate() {
-                if pos == *tile {
-                    // If snake is me, subtract food from area. Return available area
-                    if snake.get_id() == YOU_ID {
-                        if snake.get_length() - i - 1 > current_area as usize - food_eaten {
-                            return current_area;
-                        } else {
-                            return max_area;
-                        }
-                    } else if snake.get_length() - i - 1 > current_area as usize {
-                        return current_area;
-                    } else {
-                        return max_area;
-                    }
-                }
+        if let Some((id, remaining)) = self.occupant(pos) {
+            // Constrictor tails never move, so there's nothing to chase
+            if ruleset.is_constrictor() {
+                return current_area;
+            }
+
+            // If snake is me, subtract food from area. Return available area
+            let free_area = if id == YOU_ID {
+                current_area as usize - food_eaten
+            } else {
+                current_area as usize
+            };
+            if remaining > free_area {
+                return current_area;
+            } else {
+                return max_area;
             }
         }
 
         current_area += 1;
-        gone.push(pos);
+        visited[tile] = true;
 
         // Find the largest area from the current position
         let mut largest_area = current_area;
-        for tile in &pos.get_adjacent() {
-            // Discard paths of alternate routes, keep paths used to get here
-            gone.truncate(current_area as usize);
-            let new_area = self.longest_path(*tile, current_area, max_area, gone, food_eaten);
+        for next in self.get_adjacent(pos) {
+            let new_area = self.longest_path(
+                ruleset,
+                next,
+                current_area,
+                max_area,
+                visited,
+                food_eaten,
+                health,
+            );
             if new_area >= max_area {
-                return new_area;
+                largest_area = new_area;
+                break;
             }
             if new_area > largest_area {
                 largest_area = new_area;
             }
         }
 
+        // Leave the tile free for alternate routes
+        visited[tile] = false;
         largest_area
     }
 }
@@ -83,9 +113,19 @@ mod tests {
     #[test]
     fn test_longest_path_closed() {
         let board = load_object!(Board, "check_area_closed-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_closed-01", _TEST_PATH);
         let pos = board.get_snakes()[0].get_head().get_left();
-
-        let result = board.longest_path(pos, 0, 10, &mut Vec::with_capacity(10), 0);
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            10,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
 
         assert_eq!(result, 5);
     }
@@ -93,19 +133,119 @@ mod tests {
     #[test]
     fn test_longest_path_miss() {
         let board = load_object!(Board, "check_area_closed-02", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_closed-02", _TEST_PATH);
         let pos = board.get_snakes()[0].get_head().get_down();
-
-        let result = board.longest_path(pos, 0, 10, &mut Vec::with_capacity(10), 0);
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            10,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
 
         assert_eq!(result, 5);
     }
 
+    #[test]
+    fn test_longest_path_hazard() {
+        let board = load_object!(Board, "check_area_hazard-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_hazard-01", _TEST_PATH);
+        let pos = board.get_snakes()[0].get_head().get_up();
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            10,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
+
+        assert_eq!(result, 1);
+    }
+
+    #[test]
+    fn test_longest_path_royale() {
+        let board = load_object!(Board, "check_area_royale-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_royale-01", _TEST_PATH);
+        let pos = board.get_snakes()[0].get_head().get_left();
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            2,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
+
+        assert_eq!(result, 0);
+    }
+
+    #[test]
+    fn test_longest_path_wrapped() {
+        let board = load_object!(Board, "check_area_wrapped-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_wrapped-01", _TEST_PATH);
+        let pos = board.wrap(board.get_snakes()[0].get_head().get_left());
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            10,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
+
+        assert_eq!(result, 10);
+    }
+
+    #[test]
+    fn test_longest_path_constrictor() {
+        let board = load_object!(Board, "check_area_constrictor-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_constrictor-01", _TEST_PATH);
+        let pos = board.get_snakes()[0].get_head().get_down();
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            6,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
+
+        assert_eq!(result, 0);
+    }
+
     #[test]
     fn test_longest_path_hit() {
         let board = load_object!(Board, "check_area_open-02", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_open-02", _TEST_PATH);
         let pos = board.get_snakes()[0].get_head().get_down();
-
-        let result = board.longest_path(pos, 0, 10, &mut Vec::with_capacity(10), 0);
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            10,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
 
         assert_eq!(result, 10);
     }
@@ -113,9 +253,19 @@ mod tests {
     #[test]
     fn test_longest_path_food() {
         let board = load_object!(Board, "check_area_closed-03", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_closed-03", _TEST_PATH);
         let pos = board.get_snakes()[0].get_head().get_down();
-
-        let result = board.longest_path(pos, 0, 10, &mut Vec::with_capacity(10), 0);
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            10,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
 
         assert_eq!(result, 5);
     }
@@ -123,9 +273,19 @@ mod tests {
     #[test]
     fn test_longest_path_open() {
         let board = load_object!(Board, "check_area_open-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_open-01", _TEST_PATH);
         let pos = board.get_snakes()[0].get_head().get_up();
-
-        let result = board.longest_path(pos, 0, 30, &mut Vec::with_capacity(30), 0);
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            30,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
 
         assert_eq!(result, 30);
     }
@@ -133,9 +293,19 @@ mod tests {
     #[test]
     fn test_longest_path_route() {
         let board = load_object!(Board, "check_area_route-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_route-01", _TEST_PATH);
         let pos = board.get_snakes()[0].get_head().get_down();
-
-        let result = board.longest_path(pos, 0, 10, &mut Vec::with_capacity(10), 0);
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            10,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
 
         assert_eq!(result, 10);
     }
@@ -143,9 +313,19 @@ mod tests {
     #[test]
     fn test_longest_path_real() {
         let board = load_object!(Board, "check_area_route-02", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_route-02", _TEST_PATH);
         let pos = board.get_snakes()[0].get_head().get_down();
-
-        let result = board.longest_path(pos, 0, 27, &mut Vec::with_capacity(10), 0);
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            27,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
 
         assert_eq!(result, 27);
     }
@@ -153,9 +333,19 @@ mod tests {
     #[test]
     fn test_longest_path_tail() {
         let board = load_object!(Board, "check_area_route-03", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "check_area_route-03", _TEST_PATH);
         let pos = board.get_snakes()[0].get_head().get_down();
-
-        let result = board.longest_path(pos, 0, 6, &mut Vec::with_capacity(6), 0);
+        let health = board.get_snakes()[0].get_health();
+
+        let result = board.longest_path(
+            &ruleset,
+            pos,
+            0,
+            6,
+            &mut vec![false; board.tile_count()],
+            0,
+            health,
+        );
 
         assert_eq!(result, 6);
     }
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::battlesnake::Battlesnake;
+use crate::board::Board;
+use crate::constants::{DIRECTIONS, MCTS_EXPLORATION, MCTS_ROLLOUT, YOU_ID};
+use crate::direction::Direction;
+use crate::rng::Rng;
+use crate::ruleset::Ruleset;
+
+// Visits and total reward of one move of one snake
+#[derive(Clone, Copy, Default)]
+struct MoveStats {
+    visits: u32,
+    reward: f64,
+}
+
+// A position in the search tree
+// Every snake keeps its own move statistics, so moves are chosen independently (decoupled UCT)
+struct Node {
+    board: Board,
+    visits: u32,
+    stats: Vec<[MoveStats; DIRECTIONS]>,
+    // Child positions by joint move, encoded like the minimax search
+    children: HashMap<usize, usize>,
+}
+
+impl Node {
+    fn new(board: Board) -> Node {
+        let stats = vec![[MoveStats::default(); DIRECTIONS]; board.snakes.len()];
+        Node {
+            board,
+            visits: 0,
+            stats,
+            children: HashMap::new(),
+        }
+    }
+
+    // Game over for me
+    fn is_terminal(&self) -> bool {
+        self.board.get_snake(YOU_ID).is_none()
+    }
+
+    // Picks the move of snakes[index] with the best upper confidence bound, untried moves first
+    fn select(&self, index: usize) -> usize {
+        let log_visits = (self.visits.max(1) as f64).ln();
+        let mut best_option = 0;
+        let mut best_bound = f64::MIN;
+
+        for (option, stats) in self.stats[index].iter().enumerate() {
+            if stats.visits == 0 {
+                return option;
+            }
+
+            let visits = stats.visits as f64;
+            let bound = stats.reward / visits + MCTS_EXPLORATION * (log_visits / visits).sqrt();
+            if bound > best_bound {
+                best_bound = bound;
+                best_option = option;
+            }
+        }
+
+        best_option
+    }
+}
+
+impl Board {
+    /*
+    Monte Carlo tree search, all snakes move at once
+    Runs until the budget is spent or max_iterations have run
+    Returns how often each of my moves was visited, indexed by Direction
+    */
+    pub fn mcts(
+        &self,
+        ruleset: &Ruleset,
+        budget: Duration,
+        max_iterations: u32,
+        seed: u64,
+    ) -> [u32; DIRECTIONS + 1] {
+        let start = Instant::now();
+        let mut rng = Rng::new(seed);
+        let mut nodes = vec![Node::new(self.clone())];
+        let mut iterations = 0;
+
+        while iterations < max_iterations && start.elapsed() < budget && !nodes[0].is_terminal() {
+            // Selection, follow the best moves until a new position is reached
+            let mut path: Vec<(usize, Vec<usize>)> = Vec::new();
+            let mut current = 0;
+
+            while !nodes[current].is_terminal() {
+                let node = &nodes[current];
+                let options: Vec<usize> = (0..node.board.snakes.len())
+                    .map(|index| node.select(index))
+                    .collect();
+                let joint_move = options
+                    .iter()
+                    .enumerate()
+                    .map(|(j, option)| option * DIRECTIONS.pow(j as u32))
+                    .sum();
+                path.push((current, options));
+
+                if let Some(&child) = node.children.get(&joint_move) {
+                    current = child;
+                    continue;
+                }
+
+                // Expansion, add the position after this joint move
+                let mut board = node.board.clone();
+                let moves: Vec<Direction> = path
+                    .last()
+                    .unwrap()
+                    .1
+                    .iter()
+                    .enumerate()
+                    .map(|(j, &option)| board.snakes[j].get_options()[option])
+                    .collect();
+                board.make_move(ruleset, &moves);
+
+                nodes.push(Node::new(board));
+                let child = nodes.len() - 1;
+                nodes[current].children.insert(joint_move, child);
+                current = child;
+                break;
+            }
+
+            // Simulation
+            let rewards = nodes[current].board.rollout(ruleset, &mut rng);
+
+            // Backpropagation, each snake learns about its own move
+            for (node_index, options) in path {
+                let node = &mut nodes[node_index];
+                node.visits += 1;
+
+                for (j, option) in options.into_iter().enumerate() {
+                    let id = node.board.snakes[j].get_id() as usize;
+                    let stats = &mut node.stats[j][option];
+                    stats.visits += 1;
+                    stats.reward += rewards[id];
+                }
+            }
+
+            iterations += 1;
+        }
+
+        // Count my visits by absolute direction
+        let root = &nodes[0];
+        let mut visits = [0; DIRECTIONS + 1];
+        if let Some(index) = root.board.snakes.iter().position(|s| s.get_id() == YOU_ID) {
+            let you = &root.board.snakes[index];
+            for (option, direction) in you.get_options().into_iter().enumerate() {
+                visits[direction.index()] = root.stats[index][option].visits;
+            }
+        }
+        visits
+    }
+
+    // Plays the game forward with a cheap policy and returns the reward of every snake
+    // Surviving snakes earn 0.5 plus their share of the area controlled at the end
+    fn rollout(&self, ruleset: &Ruleset, rng: &mut Rng) -> Vec<f64> {
+        let mut board = self.clone();
+
+        for _ in 0..MCTS_ROLLOUT {
+            if board.get_snake(YOU_ID).is_none() || board.snakes.len() < 2 {
+                break;
+            }
+
+            let moves: Vec<Direction> = board
+                .snakes
+                .iter()
+                .map(|snake| board.rollout_move(snake, rng))
+                .collect();
+            board.make_move(ruleset, &moves);
+        }
+
+        let areas = board.area_controlled(ruleset);
+        let total_area: i32 = areas.iter().map(|&area| area.max(0)).sum();
+        let mut rewards = vec![0.0; self.max_snakes];
+        for snake in &board.snakes {
+            let area = areas[snake.get_id() as usize].max(0);
+            rewards[snake.get_id() as usize] = 0.5 + 0.5 * area as f64 / total_area.max(1) as f64;
+        }
+
+        // Squad-mates share the best reward of their squad
+        for snake in &board.snakes {
+            for ally in board.snakes.iter().filter(|&other| snake.is_ally(other)) {
+                let id = snake.get_id() as usize;
+                rewards[id] = rewards[id].max(rewards[ally.get_id() as usize]);
+            }
+        }
+
+        rewards
+    }
+
+    // Picks a random move among the ones leaving snake the most open directions
+    fn rollout_move(&self, snake: &Battlesnake, rng: &mut Rng) -> Direction {
+        let options = snake.get_options();
+        let scores = options.map(|direction| {
+            let pos = self.wrap(snake.get_move(direction));
+            if self.is_out_of_bounds(pos) || self.body_collision(pos) {
+                return -1;
+            }
+
+            let mut moved = snake.clone();
+            moved.move_to(pos);
+            self.open_directions(&moved)
+        });
+
+        let best_score = *scores.iter().max().unwrap();
+        let best: Vec<Direction> = options
+            .into_iter()
+            .zip(scores)
+            .filter(|&(_, score)| score == best_score)
+            .map(|(direction, _)| direction)
+            .collect();
+
+        best[rng.gen_range(best.len())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::_TEST_PATH;
+    use crate::direction::Direction;
+    use crate::load_object;
+    use std::time::Duration;
+
+    // mcts()
+    #[test]
+    fn test_mcts_visits() {
+        let board = load_object!(Board, "test_board-03", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "test_board-03", _TEST_PATH);
+
+        let visits = board.mcts(&ruleset, Duration::from_secs(60), 300, 1);
+
+        // Every iteration visits one of my three moves, never my neck
+        assert_eq!(visits.iter().sum::<u32>(), 300);
+        assert_eq!(visits.iter().filter(|&&count| count == 0).count(), 1);
+    }
+
+    #[test]
+    fn test_mcts_avoid_headon() {
+        let board = load_object!(Board, "test_board-06", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "test_board-06", _TEST_PATH);
+
+        let visits = board.mcts(&ruleset, Duration::from_secs(60), 2000, 1);
+        let best = Direction::ALL
+            .into_iter()
+            .max_by_key(|direction| visits[direction.index()])
+            .unwrap();
+
+        // Same choice as the minimax search
+        assert_eq!(best, Direction::Down);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::board::Board;
+use crate::coordinate::Coordinate;
+
+// The snake on a tile and the turn its body leaves the tile
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Occupant {
+    id: u8,
+    until: i32,
+}
+
+impl Board {
+    // Returns the number of tiles on the board
+    pub fn tile_count(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    // Returns the position of pos in grids covering the board, pos must be in bounds
+    pub fn tile_index(&self, pos: Coordinate) -> usize {
+        (self.width * pos.get_y() + pos.get_x()) as usize
+    }
+
+    // Returns the id of the snake on pos and how many more moves its body stays there
+    // Stays 0 for tails, which move out of the way on the next turn
+    pub fn occupant(&self, pos: Coordinate) -> Option<(u8, usize)> {
+        if !self.occupancy_valid || self.is_out_of_bounds(pos) {
+            return self.find_occupant(pos);
+        }
+
+        let occupant = self.occupancy[self.tile_index(pos)];
+        if occupant.until > self.occupancy_turn {
+            Some((
+                occupant.id,
+                (occupant.until - self.occupancy_turn - 1) as usize,
+            ))
+        } else {
+            None
+        }
+    }
+
+    // Finds the occupant of pos by searching every snake, used while the grid is out of date
+    fn find_occupant(&self, pos: Coordinate) -> Option<(u8, usize)> {
+        for snake in &self.snakes {
+            let body = snake.get_body();
+            if let Some(i) = body.iter().position(|&tile| tile == pos) {
+                return Some((snake.get_id(), body.len() - i - 1));
+            }
+        }
+        None
+    }
+
+    // Rebuilds the grid from the snake bodies
+    pub fn rebuild_occupancy(&mut self) {
+        let mut occupancy = vec![Occupant::default(); self.tile_count()];
+
+        for snake in &self.snakes {
+            let length = snake.get_body().len();
+            for (i, &pos) in snake.get_body().iter().enumerate() {
+                if self.is_out_of_bounds(pos) {
+                    continue;
+                }
+
+                let until = self.turn + (length - i) as i32;
+                let tile = &mut occupancy[self.tile_index(pos)];
+                if until > tile.until {
+                    *tile = Occupant {
+                        id: snake.get_id(),
+                        until,
+                    };
+                }
+            }
+        }
+
+        self.occupancy = occupancy;
+        self.occupancy_turn = self.turn;
+        self.occupancy_valid = true;
+    }
+
+    // Marks the grid as out of date, it's rebuilt by the next game_step
+    pub fn invalidate_occupancy(&mut self) {
+        self.occupancy_valid = false;
+    }
+
+    pub fn is_occupancy_valid(&self) -> bool {
+        self.occupancy_valid
+    }
+
+    // Moves snakes[index] to pos and updates the grid if it's in use
+    // Returns what the grid held at pos before, so the move can be undone
+    pub fn move_snake(&mut self, index: usize, pos: Coordinate) -> Option<Occupant> {
+        self.snakes[index].move_to(pos);
+
+        if !self.occupancy_valid || self.is_out_of_bounds(pos) {
+            return None;
+        }
+
+        let snake = &self.snakes[index];
+        let until = self.occupancy_turn + snake.get_body().len() as i32;
+        let tile = self.tile_index(pos);
+        let before = self.occupancy[tile];
+        self.occupancy[tile] = Occupant {
+            id: snake.get_id(),
+            until,
+        };
+        Some(before)
+    }
+
+    // Puts back what the grid held at pos before move_snake
+    pub fn restore_occupant(&mut self, pos: Coordinate, occupant: Occupant) {
+        let tile = self.tile_index(pos);
+        self.occupancy[tile] = occupant;
+    }
+
+    // Moves the grid to the next turn, before the snakes move
+    pub fn advance_occupancy(&mut self) {
+        self.occupancy_turn += 1;
+    }
+
+    // Moves the grid back to the current turn, after the snakes are restored
+    pub fn reset_occupancy_turn(&mut self) {
+        self.occupancy_turn = self.turn;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::constants::{_TEST_PATH, DIRECTIONS};
+    use crate::coordinate::Coordinate;
+    use crate::direction::Direction;
+    use crate::load_object;
+
+    // Checks the grid against searching every snake, on every tile
+    fn assert_occupancy(board: &Board) {
+        assert!(board.is_occupancy_valid());
+        for x in 0..board.get_width() {
+            for y in 0..board.get_height() {
+                let pos = Coordinate::new(x, y);
+                assert_eq!(board.occupant(pos), board.find_occupant(pos), "{:?}", pos);
+            }
+        }
+    }
+
+    // occupant()
+    #[test]
+    fn test_occupancy_new() {
+        let board = load_object!(Board, "test_board-04", _TEST_PATH);
+
+        assert_occupancy(&board);
+    }
+
+    #[test]
+    fn test_occupancy_make_unmake_move() {
+        for name in [
+            "test_board-03",
+            "eat-01-before",
+            "squad_elimination-01-before",
+        ] {
+            let mut board = load_object!(Board, name, _TEST_PATH);
+            let ruleset = load_object!(Ruleset, name, _TEST_PATH);
+            let num_snakes = board.get_snakes().len() as u32;
+
+            for i in 0..DIRECTIONS.pow(num_snakes) {
+                let moves: Vec<Direction> = board
+                    .get_snakes()
+                    .iter()
+                    .enumerate()
+                    .map(|(j, snake)| {
+                        snake.get_options()[(i / DIRECTIONS.pow(j as u32)) % DIRECTIONS]
+                    })
+                    .collect();
+
+                let undo = board.make_move(&ruleset, &moves);
+                assert_occupancy(&board);
+
+                board.unmake_move(undo);
+                assert_occupancy(&board);
+            }
+        }
+    }
+
+    #[test]
+    fn test_occupancy_invalid() {
+        let mut board = load_object!(Board, "body_collision-01-before", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "body_collision-01-before", _TEST_PATH);
+
+        // Changing snakes directly falls back to searching until the next game_step
+        board.get_snakes_mut().pop();
+        assert!(!board.is_occupancy_valid());
+        assert_eq!(board.occupant(Coordinate::new(3, 1)), None);
+
+        board.game_step(&ruleset);
+        assert_occupancy(&board);
+    }
+}
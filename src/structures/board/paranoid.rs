@@ -0,0 +1,202 @@
+use std::cmp::{max, min};
+
+use crate::battlesnake::Battlesnake;
+use crate::board::Board;
+use crate::config::Profile;
+use crate::constants::{DIRECTIONS, YOU_ID};
+use crate::direction::Direction;
+use crate::evaluation::Evaluation;
+use crate::ruleset::Ruleset;
+
+impl Board {
+    // Moves self in direction and predicts future turns with the paranoid search
+    pub fn check_paranoid(
+        mut self,
+        direction: Direction,
+        ruleset: &Ruleset,
+        profile: &Profile,
+        current_level: i32,
+        max_level: i32,
+    ) -> Evaluation {
+        let mut moves = Vec::with_capacity(self.snakes.len());
+        moves.push(direction);
+        self.paranoid_turn(
+            ruleset,
+            profile,
+            &mut moves,
+            current_level,
+            max_level,
+            Evaluation::MIN,
+            Evaluation::MAX,
+        )
+    }
+
+    /*
+    Paranoid alpha-beta search, returns my score
+    Every opponent is assumed to play against me as one coalition, while squad-mates help me.
+    Moves are chosen one snake at a time, so whole subtrees can be cut off,
+    but are only resolved by game_step once every snake has moved.
+    */
+    pub fn paranoid(
+        &mut self,
+        ruleset: &Ruleset,
+        profile: &Profile,
+        current_level: i32,
+        max_level: i32,
+        alpha: Evaluation,
+        beta: Evaluation,
+    ) -> Evaluation {
+        // Out of time, the caller throws this search away
+        if self.is_past_deadline() {
+            return Evaluation::default();
+        }
+
+        // End case. Return if I'm dead or current_level >= max_level
+        if current_level >= max_level || self.get_snake(YOU_ID).is_none() {
+            return self.evaluate(ruleset, profile)[YOU_ID as usize];
+        }
+
+        let mut moves = Vec::with_capacity(self.snakes.len());
+        self.paranoid_turn(
+            ruleset,
+            profile,
+            &mut moves,
+            current_level,
+            max_level,
+            alpha,
+            beta,
+        )
+    }
+
+    // Chooses a move for the next snake without one, then the snakes after it, then steps the game
+    #[allow(clippy::too_many_arguments)]
+    fn paranoid_turn(
+        &mut self,
+        ruleset: &Ruleset,
+        profile: &Profile,
+        moves: &mut Vec<Direction>,
+        current_level: i32,
+        max_level: i32,
+        mut alpha: Evaluation,
+        mut beta: Evaluation,
+    ) -> Evaluation {
+        // Every snake has a move
+        let index = moves.len();
+        if index == self.snakes.len() {
+            let undo = self.make_move(ruleset, moves);
+            let result = self.paranoid(ruleset, profile, current_level + 1, max_level, alpha, beta);
+            self.unmake_move(undo);
+            return result;
+        }
+
+        let you = &self.snakes[YOU_ID as usize];
+        let snake = &self.snakes[index];
+        let maximising = snake.get_id() == YOU_ID || snake.is_ally(you);
+        let mut best = if maximising {
+            Evaluation::MIN
+        } else {
+            Evaluation::MAX
+        };
+
+        for direction in self.order_moves(snake, maximising) {
+            moves.push(direction);
+            let result = self.paranoid_turn(
+                ruleset,
+                profile,
+                moves,
+                current_level,
+                max_level,
+                alpha,
+                beta,
+            );
+            moves.pop();
+
+            if maximising {
+                best = max(best, result);
+                alpha = max(alpha, best);
+            } else {
+                best = min(best, result);
+                beta = min(beta, best);
+            }
+
+            // The other side already has a better option elsewhere
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best
+    }
+
+    // Returns the moves of snake, most promising first, so more of the tree gets cut off
+    // Moves into walls or bodies go last, opponents try moves towards me first
+    fn order_moves(&self, snake: &Battlesnake, maximising: bool) -> [Direction; DIRECTIONS] {
+        let you = self.snakes[YOU_ID as usize].get_head();
+        let mut options = snake.get_options();
+
+        options.sort_by_key(|&direction| {
+            let pos = self.wrap(snake.get_move(direction));
+            let blocked = self.is_out_of_bounds(pos) || self.body_collision(pos);
+            let distance = if maximising {
+                0
+            } else {
+                self.distance(pos, you)
+            };
+            (blocked, distance)
+        });
+
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Profile;
+    use crate::constants::_TEST_PATH;
+    use crate::direction::Direction;
+    use crate::evaluation::Evaluation;
+    use crate::load_object;
+    use crate::transposition_table::TranspositionTable;
+
+    // paranoid()
+    #[test]
+    fn test_paranoid() {
+        let board = load_object!(Board, "test_board-03", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "test_board-03", _TEST_PATH);
+
+        let result = board.clone().paranoid(
+            &ruleset,
+            &Profile::default(),
+            0,
+            2,
+            Evaluation::MIN,
+            Evaluation::MAX,
+        );
+
+        assert!(result.is_alive());
+    }
+
+    // Searching one turn, both searches take the worst reply for me
+    #[test]
+    fn test_paranoid_one_turn() {
+        let board = load_object!(Board, "test_board-04", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "test_board-04", _TEST_PATH);
+
+        for direction in Direction::ALL {
+            let paranoid =
+                board
+                    .clone()
+                    .check_paranoid(direction, &ruleset, &Profile::default(), 0, 1);
+            let minimax = board.clone().check_move(
+                direction,
+                &ruleset,
+                &Profile::default(),
+                &TranspositionTable::new(0),
+                0,
+                1,
+            );
+
+            assert_eq!(paranoid, minimax);
+        }
+    }
+}
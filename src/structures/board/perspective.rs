@@ -0,0 +1,57 @@
+use crate::board::Board;
+use crate::constants::YOU_ID;
+
+impl Board {
+    // Returns self as seen by the snake with id, for deciding its move
+    // That snake becomes YOU_ID and moves first, trading ids with the snake that was
+    pub fn from_perspective(&self, id: u8) -> Board {
+        let mut board = self.clone();
+        if id == YOU_ID {
+            return board;
+        }
+
+        for snake in &mut board.snakes {
+            if snake.get_id() == id {
+                snake.set_id(YOU_ID);
+            } else if snake.get_id() == YOU_ID {
+                snake.set_id(id);
+            }
+        }
+        if let Some(index) = board.snakes.iter().position(|s| s.get_id() == YOU_ID) {
+            let snake = board.snakes.remove(index);
+            board.snakes.insert(0, snake);
+        }
+
+        // The grid and hash hold ids, so they have to be rebuilt with the new ones
+        board.rebuild_occupancy();
+        board.rebuild_zobrist_hash();
+        board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::{_TEST_PATH, YOU_ID};
+    use crate::load_object;
+
+    // from_perspective()
+    #[test]
+    fn test_from_perspective() {
+        let board = load_object!(Board, "test_board-04", _TEST_PATH);
+        let other = board.get_snakes()[2].clone();
+        let you = board.get_snakes()[0].clone();
+
+        let seen = board.from_perspective(other.get_id());
+
+        assert_eq!(seen.get_snakes()[0].get_head(), other.get_head());
+        assert_eq!(seen.get_snakes()[0].get_id(), YOU_ID);
+        assert_eq!(
+            seen.get_snake(other.get_id()).unwrap().get_head(),
+            you.get_head()
+        );
+        assert_eq!(
+            seen.occupant(other.get_head()),
+            Some((YOU_ID, other.get_body().len() - 1))
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use crate::board::Board;
+use crate::direction::Direction;
+use crate::rng::Rng;
+use crate::ruleset::Ruleset;
+
+// How game_step shrinks the safe area of a royale game
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RoyaleShrink {
+    // Every side shrinks at once, covering whichever side the game picks, for searching
+    Projected,
+    // One side shrinks, picked like the royale rules using a seeded random number generator
+    Sampled(Rng),
+}
+
+impl Board {
+    // Returns true if the safe area of a royale game shrinks on this turn
+    pub fn is_shrink_turn(&self, ruleset: &Ruleset) -> bool {
+        let shrink_every_n_turns = ruleset.get_shrink_every_n_turns();
+        ruleset.is_royale() && shrink_every_n_turns > 0 && self.turn % shrink_every_n_turns == 0
+    }
+
+    // Turns the edge of the safe area into hazards, one side or all of them depending on royale_shrink
+    pub fn shrink_royale(&mut self) {
+        match &mut self.royale_shrink {
+            RoyaleShrink::Projected => {
+                for side in Direction::ALL {
+                    self.shrink(side);
+                }
+            }
+            RoyaleShrink::Sampled(rng) => {
+                let side = Direction::ALL[rng.gen_range(Direction::ALL.len())];
+                self.shrink(side);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::_TEST_PATH;
+    use crate::load_object;
+
+    // shrink_royale()
+    #[test]
+    fn test_shrink_royale_sampled() {
+        let mut board = load_object!(Board, "royale_shrink-01-before", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "royale_shrink-01-before", _TEST_PATH);
+        board.set_royale_shrink(RoyaleShrink::Sampled(Rng::new(3)));
+        let (bottom_left, top_right) = board.safe_bounds;
+        let mut sampled = board.clone();
+        let mut projected = board.clone();
+        projected.set_royale_shrink(RoyaleShrink::Projected);
+
+        let turn = ruleset.get_shrink_every_n_turns();
+        sampled.set_turn(turn);
+        projected.set_turn(turn);
+        assert!(sampled.is_shrink_turn(&ruleset));
+        sampled.shrink_royale();
+        projected.shrink_royale();
+
+        // Exactly one side moves in, and only onto tiles the projection covers
+        let (new_bottom_left, new_top_right) = sampled.safe_bounds;
+        let moved = [
+            new_bottom_left.get_x() != bottom_left.get_x(),
+            new_bottom_left.get_y() != bottom_left.get_y(),
+            new_top_right.get_x() != top_right.get_x(),
+            new_top_right.get_y() != top_right.get_y(),
+        ];
+        assert_eq!(moved.iter().filter(|&&moved| moved).count(), 1);
+        assert!(sampled.get_hazards().len() > board.get_hazards().len());
+        assert!(sampled
+            .get_hazards()
+            .iter()
+            .all(|&hazard| projected.is_hazard(hazard)));
+
+        // Turns between shrinks leave the board alone
+        sampled.set_turn(turn + 1);
+        assert!(!sampled.is_shrink_turn(&ruleset));
+    }
+}
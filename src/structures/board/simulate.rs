@@ -1,87 +1,150 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::max;
+use std::time::Instant;
+
 use crate::board::Board;
-use crate::constants::{DIRECTIONS, DRAWING, YOU_ID};
+use crate::config::Profile;
+use crate::constants::{DIRECTIONS, DRAWING, SEARCH_MODE, YOU_ID};
+use crate::direction::Direction;
+use crate::evaluation::Evaluation;
+use crate::opponent_model::OpponentModel;
 use crate::ruleset::Ruleset;
+use crate::transposition_table::TranspositionTable;
+
+// How calculate_move predicts future turns
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    // Every snake picks its best move against the worst replies, all snakes move at once
+    Minimax,
+    // Alpha-beta search of me against a coalition of every opponent, searches deeper in crowded games
+    Paranoid,
+    // Monte Carlo tree search, picks the move visited most often
+    Mcts,
+    // Like minimax, but the replies to my move are averaged by how likely the opponent model finds them
+    Expected,
+}
 
-impl Board {
-    // Moves self down and predicts future turns
-    pub fn check_down(mut self, ruleset: &Ruleset, current_level: i32, max_level: i32) -> u64 {
-        let snake = &mut self.snakes[YOU_ID as usize];
-        let down = snake.get_down();
-        snake.move_to(down);
-        self.recursion_entry(ruleset, current_level, max_level)
+impl Default for SearchMode {
+    fn default() -> SearchMode {
+        SEARCH_MODE
     }
+}
 
-    // Moves self up and predicts future turns
-    pub fn check_up(mut self, ruleset: &Ruleset, current_level: i32, max_level: i32) -> u64 {
-        let snake = &mut self.snakes[YOU_ID as usize];
-        let up = snake.get_up();
-        snake.move_to(up);
-        self.recursion_entry(ruleset, current_level, max_level)
+impl Board {
+    // Returns true once the deadline has passed, a search still running then is cut off
+    pub fn is_past_deadline(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
     }
 
-    // Moves self right and predicts future turns
-    pub fn check_right(
-        mut self,
+    // Moves self in direction and predicts future turns
+    pub fn check_move(
+        &self,
+        direction: Direction,
         ruleset: &Ruleset,
+        profile: &Profile,
+        table: &TranspositionTable,
         current_level: i32,
         max_level: i32,
-    ) -> u64 {
-        let snake = &mut self.snakes[YOU_ID as usize];
-        let right = snake.get_right();
-        snake.move_to(right);
-        self.recursion_entry(ruleset, current_level, max_level)
+    ) -> Evaluation {
+        self.recursion_entry(
+            direction,
+            ruleset,
+            profile,
+            table,
+            None,
+            current_level,
+            max_level,
+        )
     }
 
-    // Moves self left and predicts future turns
-    pub fn check_left(mut self, ruleset: &Ruleset, current_level: i32, max_level: i32) -> u64 {
-        let snake = &mut self.snakes[YOU_ID as usize];
-        let left = snake.get_left();
-        snake.move_to(left);
-        self.recursion_entry(ruleset, current_level, max_level)
+    // Moves self in direction and predicts future turns, expecting the replies predicted by model
+    // Later turns still assume the worst replies
+    #[allow(clippy::too_many_arguments)]
+    pub fn check_move_expected(
+        &self,
+        direction: Direction,
+        ruleset: &Ruleset,
+        profile: &Profile,
+        table: &TranspositionTable,
+        model: &OpponentModel,
+        current_level: i32,
+        max_level: i32,
+    ) -> Evaluation {
+        self.recursion_entry(
+            direction,
+            ruleset,
+            profile,
+            table,
+            Some(model),
+            current_level,
+            max_level,
+        )
     }
 
-    // First level of recursion, my snake has already moved
-    fn recursion_entry(self, ruleset: &Ruleset, current_level: i32, max_level: i32) -> u64 {
+    // First level of recursion, my move is already chosen
+    // The replies of the other snakes are searched in parallel, each worker on its own copy
+    // With a model the replies are averaged, and not stored since the result depends on the model
+    #[allow(clippy::too_many_arguments)]
+    fn recursion_entry(
+        &self,
+        direction: Direction,
+        ruleset: &Ruleset,
+        profile: &Profile,
+        table: &TranspositionTable,
+        model: Option<&OpponentModel>,
+        current_level: i32,
+        max_level: i32,
+    ) -> Evaluation {
         if DRAWING {
             self.draw(String::from("test")).unwrap();
         }
 
+        // Check if this position has already been searched
+        let hash = self.zobrist_hash_to_reply(direction);
+        if model.is_none() {
+            if let Some(result) = table.get(hash, max_level - current_level) {
+                return result[0];
+            }
+        }
+
         let num_snakes = self.snakes.len();
         let mut worst_boards: Vec<[i32; DIRECTIONS]> = vec![[-1; DIRECTIONS]; num_snakes - 1];
-        let mut result_boards: Vec<Vec<u64>> =
-            Vec::with_capacity(DIRECTIONS.pow(num_snakes as u32 - 1));
 
         // Iterate through all possible boards
-        for i in 0..DIRECTIONS.pow(num_snakes as u32 - 1) {
-            // Create new Board to modify
-            let mut new_board = self.clone();
-
-            if DRAWING {
-                new_board.draw(String::from("test")).unwrap();
-            }
-
-            // Move each snake to new position on new_board
-            for j in 0..num_snakes - 1 {
-                let snake = &mut new_board.snakes[j + 1];
-                let pos = snake.get_option((i / DIRECTIONS.pow(j as u32)) % DIRECTIONS);
-                snake.move_to(pos);
-            }
-
-            if DRAWING {
-                new_board.draw(String::from("test")).unwrap();
-            }
-
-            // Update new_board
-            new_board.game_step(ruleset);
-
-            if DRAWING {
-                new_board.draw(String::from("test")).unwrap();
-            }
-
-            // Get the maximin result from this position
-            let result = new_board.minimax(ruleset, current_level + 1, max_level);
-
-            // Update worst outcomes
+        let result_boards: Vec<Vec<Evaluation>> = (0..DIRECTIONS.pow(num_snakes as u32 - 1))
+            .into_par_iter()
+            .map_init(
+                || (self.clone(), Vec::with_capacity(num_snakes)),
+                |(board, moves), i| {
+                    // Choose a move for each other snake
+                    moves.clear();
+                    moves.push(direction);
+                    for j in 0..num_snakes - 1 {
+                        let option = (i / DIRECTIONS.pow(j as u32)) % DIRECTIONS;
+                        moves.push(board.snakes[j + 1].get_options()[option]);
+                    }
+
+                    // Update the board in place
+                    let undo = board.make_move(ruleset, moves);
+
+                    if DRAWING {
+                        board.draw(String::from("test")).unwrap();
+                    }
+
+                    // Get the maximin result from this position, then undo the move
+                    let result =
+                        board.minimax(ruleset, profile, table, current_level + 1, max_level);
+                    board.unmake_move(undo);
+                    result
+                },
+            )
+            .collect();
+
+        // Update worst outcomes
+        for (i, result) in result_boards.iter().enumerate() {
             for (j, snake_boards) in worst_boards.iter_mut().enumerate() {
                 let direction = (i / DIRECTIONS.pow(j as u32)) % DIRECTIONS;
                 let current_worst = snake_boards[direction];
@@ -91,57 +154,115 @@ impl Board {
                     snake_boards[direction] = i as i32;
                 }
             }
-            // Store calculated board
-            result_boards.push(result);
         }
 
         if DRAWING {
             self.draw(String::from("test")).unwrap();
         }
 
-        result_boards.iter().map(|board| board[0]).min().unwrap()
+        // Squad-mates choose the best outcome for me, opponents choose the worst
+        let you = &self.snakes[YOU_ID as usize];
+        let mut best_replies: Vec<Option<Evaluation>> = vec![None; result_boards.len()];
+        for (i, board) in result_boards.iter().enumerate() {
+            let mut opponent_moves = 0;
+            for j in 0..num_snakes - 1 {
+                if !self.snakes[j + 1].is_ally(you) {
+                    let direction = (i / DIRECTIONS.pow(j as u32)) % DIRECTIONS;
+                    opponent_moves += direction * DIRECTIONS.pow(j as u32);
+                }
+            }
+            best_replies[opponent_moves] = max(best_replies[opponent_moves], Some(board[0]));
+        }
+
+        let model = match model {
+            Some(model) => model,
+            None => {
+                let result = best_replies.into_iter().flatten().min().unwrap();
+                if !self.is_past_deadline() {
+                    table.insert(hash, max_level - current_level, &[result]);
+                }
+                return result;
+            }
+        };
+
+        // The chance of each reply is the chance of every opponent making its part of it
+        let probabilities: Vec<[f64; DIRECTIONS]> = self.snakes[1..]
+            .iter()
+            .map(|snake| model.reply_probabilities(self, snake))
+            .collect();
+        let outcomes: Vec<(f64, Evaluation)> = best_replies
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, reply)| {
+                let probability = (0..num_snakes - 1)
+                    .filter(|&j| !self.snakes[j + 1].is_ally(you))
+                    .map(|j| probabilities[j][(i / DIRECTIONS.pow(j as u32)) % DIRECTIONS])
+                    .product();
+                Some((probability, reply?))
+            })
+            .collect();
+
+        Evaluation::expected(&outcomes, profile.get_weights())
     }
 
     // Recursive minimax-ish to find score of position
-    pub fn minimax(&self, ruleset: &Ruleset, current_level: i32, max_level: i32) -> Vec<u64> {
+    pub fn minimax(
+        &mut self,
+        ruleset: &Ruleset,
+        profile: &Profile,
+        table: &TranspositionTable,
+        current_level: i32,
+        max_level: i32,
+    ) -> Vec<Evaluation> {
         if DRAWING {
             self.draw(String::from("test")).unwrap();
         }
 
+        // Out of time, the caller throws this search away
+        if self.is_past_deadline() {
+            return vec![Evaluation::default(); self.max_snakes];
+        }
+
+        // Check if this position has already been searched
+        let hash = self.zobrist_hash();
+        let depth = max_level - current_level;
+        if let Some(result) = table.get(hash, depth) {
+            return result;
+        }
+
         // End case. Return if all snakes are dead or current_level >= max_level
         if current_level >= max_level || self.snakes.is_empty() {
-            return self.evaluate();
+            let result = self.evaluate(ruleset, profile);
+            table.insert(hash, depth, &result);
+            return result;
         }
 
         let num_snakes = self.snakes.len();
         let mut worst_boards: Vec<[i32; DIRECTIONS]> = vec![[-1; DIRECTIONS]; num_snakes];
-        let mut result_boards: Vec<Vec<u64>> =
+        let mut result_boards: Vec<Vec<Evaluation>> =
             Vec::with_capacity(DIRECTIONS.pow(num_snakes as u32));
 
+        let mut moves = Vec::with_capacity(num_snakes);
+
         // Iterate through all possible boards
         for i in 0..DIRECTIONS.pow(num_snakes as u32) {
-            // Create new Board to modify
-            let mut new_board = self.clone();
-            // Move each snake to new position on new_board
+            // Choose a move for each snake
+            moves.clear();
             for j in 0..num_snakes {
-                let snake = &mut new_board.snakes[j];
-                let pos = snake.get_option((i / DIRECTIONS.pow(j as u32)) % DIRECTIONS);
-                snake.move_to(pos);
+                let option = (i / DIRECTIONS.pow(j as u32)) % DIRECTIONS;
+                moves.push(self.snakes[j].get_options()[option]);
             }
 
-            if DRAWING {
-                new_board.draw(String::from("test")).unwrap();
-            }
-
-            // Update new_board
-            new_board.game_step(ruleset);
+            // Update the board in place
+            let undo = self.make_move(ruleset, &moves);
 
             if DRAWING {
-                new_board.draw(String::from("test")).unwrap();
+                self.draw(String::from("test")).unwrap();
             }
 
-            // Get the maximin result from this position
-            let result = new_board.minimax(ruleset, current_level + 1, max_level);
+            // Get the maximin result from this position, then undo the move
+            let result = self.minimax(ruleset, profile, table, current_level + 1, max_level);
+            self.unmake_move(undo);
 
             // Update worst outcomes
             for (j, snake_boards) in worst_boards.iter_mut().enumerate() {
@@ -182,23 +303,80 @@ impl Board {
             return_board += best_direction * DIRECTIONS.pow(i as u32);
         }
 
-        // Return the best board
+        // Store and return the best board
+        // Results finished after the deadline may rest on searches that were cut off, so they aren't stored
+        if !self.is_past_deadline() {
+            table.insert(hash, depth, &result_boards[return_board]);
+        }
         result_boards.swap_remove(return_board)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::constants::_TEST_PATH;
+    use crate::config::Profile;
+    use crate::constants::{_TEST_PATH, TABLE_SIZE};
+    use crate::direction::Direction;
     use crate::load_object;
+    use crate::opponent_model::OpponentModel;
+    use crate::transposition_table::TranspositionTable;
+
+    // check_move_expected()
+    #[test]
+    fn test_check_move_expected() {
+        let board = load_object!(Board, "test_board-04", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "test_board-04", _TEST_PATH);
+        let profile = Profile::default();
+        let table = TranspositionTable::new(0);
+
+        for direction in Direction::ALL {
+            let worst = board.check_move(direction, &ruleset, &profile, &table, 0, 2);
+            let expected = board.check_move_expected(
+                direction,
+                &ruleset,
+                &profile,
+                &table,
+                &OpponentModel::default(),
+                0,
+                2,
+            );
+
+            // Surviving every reply survives the likely ones, with at least the worst score
+            if worst.is_alive() {
+                assert!(expected.is_alive());
+                assert!(expected.get_score() >= worst.get_score());
+            }
+        }
+    }
+
     // minimax()
     #[test]
     fn test_minimax() {
         let board = load_object!(Board, "test_board-03", _TEST_PATH);
         let ruleset = load_object!(Ruleset, "test_board-03", _TEST_PATH);
 
-        let result = board.minimax(&ruleset, 0, 2);
+        let result = board.clone().minimax(
+            &ruleset,
+            &Profile::default(),
+            &TranspositionTable::new(0),
+            0,
+            2,
+        );
+
+        assert!(result[0].is_alive());
+    }
+
+    #[test]
+    fn test_minimax_table() {
+        let mut board = load_object!(Board, "test_board-03", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "test_board-03", _TEST_PATH);
+        let profile = Profile::default();
+        let table = TranspositionTable::new(TABLE_SIZE);
+
+        let result = board.minimax(&ruleset, &profile, &table, 0, 2);
 
-        assert!(result[0] > 0);
+        // The second search is answered from the table
+        assert_eq!(board.minimax(&ruleset, &profile, &table, 0, 2), result);
+        assert!(table.get_hits() > 0);
     }
 }
@@ -0,0 +1,143 @@
+use crate::board::Board;
+use crate::coordinate::Coordinate;
+use crate::rng::Rng;
+use crate::ruleset::Ruleset;
+
+// How game_step adds food to the board
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FoodSpawn {
+    // No food is added, the board drains as food is eaten
+    Off,
+    // Food is added at the expected rate, on the free tile furthest from every snake
+    Expected,
+    // Food is added like the standard rules, using a seeded random number generator
+    Sampled(Rng),
+}
+
+impl Board {
+    // Adds food following the minimum food and spawn chance rules
+    pub fn spawn_food(&mut self, ruleset: &Ruleset) {
+        let minimum_food = ruleset.get_minimum_food() as usize;
+        let chance = ruleset.get_food_spawn_chance();
+
+        let amount = match &mut self.food_spawn {
+            FoodSpawn::Off => return,
+            _ if self.food.len() < minimum_food => minimum_food - self.food.len(),
+            FoodSpawn::Expected => {
+                // Spawn whenever the expected number of spawns passes a whole number
+                let expected = |turn: i32| turn * chance / 100;
+                (expected(self.turn + 1) - expected(self.turn)) as usize
+            }
+            FoodSpawn::Sampled(rng) => (chance > rng.gen_range(100) as i32) as usize,
+        };
+
+        for _ in 0..amount {
+            let free_tiles = self.find_free_tiles();
+            if free_tiles.is_empty() {
+                return;
+            }
+
+            let pos = match &mut self.food_spawn {
+                FoodSpawn::Sampled(rng) => free_tiles[rng.gen_range(free_tiles.len())],
+                _ => *free_tiles
+                    .iter()
+                    .max_by_key(|&&tile| {
+                        self.snakes
+                            .iter()
+                            .map(|snake| self.distance(tile, snake.get_head()))
+                            .min()
+                            .unwrap_or(0)
+                    })
+                    .unwrap(),
+            };
+
+            self.food.push(pos);
+        }
+    }
+
+    // Returns tiles without food, snakes, or a snake head next to them
+    fn find_free_tiles(&self) -> Vec<Coordinate> {
+        let mut free_tiles = Vec::new();
+
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let pos = Coordinate::new(x, y);
+                if !self.food.contains(&pos)
+                    && !self.body_collision(pos)
+                    && !self
+                        .snakes
+                        .iter()
+                        .any(|snake| self.get_adjacent(snake.get_head()).contains(&pos))
+                {
+                    free_tiles.push(pos);
+                }
+            }
+        }
+
+        free_tiles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FoodSpawn;
+    use crate::constants::_TEST_PATH;
+    use crate::load_object;
+    use crate::rng::Rng;
+
+    // spawn_food()
+    #[test]
+    fn test_spawn_food_off() {
+        let mut board = load_object!(Board, "find_closest_food_none-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "find_closest_food_none-01", _TEST_PATH);
+
+        board.spawn_food(&ruleset);
+
+        assert!(board.get_food().is_empty());
+    }
+
+    #[test]
+    fn test_spawn_food_minimum() {
+        let mut board = load_object!(Board, "find_closest_food_none-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "find_closest_food_none-01", _TEST_PATH);
+        board.set_food_spawn(FoodSpawn::Expected);
+
+        board.spawn_food(&ruleset);
+
+        let food = board.get_food()[0];
+        assert_eq!(board.get_food().len(), 1);
+        assert!(!board.body_collision(food));
+    }
+
+    #[test]
+    fn test_spawn_food_expected() {
+        let mut board = load_object!(Board, "food-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "food-01", _TEST_PATH);
+        board.set_food_spawn(FoodSpawn::Expected);
+        let food = board.get_food().len();
+
+        // A 25% chance spawns one food every four turns
+        for _ in 0..4 {
+            board.spawn_food(&ruleset);
+            board.increment_turn();
+        }
+
+        assert_eq!(board.get_food().len(), food + 1);
+    }
+
+    #[test]
+    fn test_spawn_food_sampled() {
+        let mut board_1 = load_object!(Board, "find_closest_food_none-01", _TEST_PATH);
+        let ruleset = load_object!(Ruleset, "find_closest_food_none-01", _TEST_PATH);
+        board_1.set_food_spawn(FoodSpawn::Sampled(Rng::new(3)));
+        let mut board_2 = board_1.clone();
+
+        for _ in 0..10 {
+            board_1.spawn_food(&ruleset);
+            board_2.spawn_food(&ruleset);
+        }
+
+        assert!(!board_1.get_food().is_empty());
+        assert_eq!(board_1, board_2);
+    }
+}
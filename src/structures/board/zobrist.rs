@@ -0,0 +1,268 @@
+use std::sync::OnceLock;
+
+use crate::battlesnake::Battlesnake;
+use crate::board::Board;
+use crate::constants::{HEALTH_BUCKET, MAX_HEALTH, ZOBRIST_SEED};
+use crate::coordinate::Coordinate;
+use crate::direction::Direction;
+use crate::rng::Rng;
+
+// Boards up to SIZE - 1 tiles across get a key for every tile, with a border for heads that left them
+// Bigger boards and games of more than SNAKES snakes share keys, which only makes collisions likelier
+const SIZE: i32 = 32;
+const TILES: usize = (SIZE * SIZE) as usize;
+const SNAKES: usize = 8;
+const HEALTHS: usize = (MAX_HEALTH / HEALTH_BUCKET) as usize + 1;
+// Stacks of more hazards share keys with smaller ones
+const STACKS: usize = 8;
+// Turns share keys this far apart, positions that far apart never meet in the table
+const TURNS: usize = 1024;
+
+static KEYS: OnceLock<ZobristKeys> = OnceLock::new();
+
+// The random keys for each feature of a position, drawn once from ZOBRIST_SEED
+pub struct ZobristKeys {
+    head: Vec<u64>,
+    body: Vec<u64>,
+    length: Vec<u64>,
+    health: Vec<u64>,
+    food: Vec<u64>,
+    hazard: Vec<u64>,
+    turn: Vec<u64>,
+    to_reply: Vec<u64>,
+}
+
+fn tile(pos: Coordinate) -> usize {
+    (pos.get_y().rem_euclid(SIZE) * SIZE + pos.get_x().rem_euclid(SIZE)) as usize
+}
+
+fn snake(id: u8) -> usize {
+    id as usize % SNAKES
+}
+
+impl ZobristKeys {
+    fn new() -> ZobristKeys {
+        let mut rng = Rng::new(ZOBRIST_SEED);
+        let mut table = |size: usize| -> Vec<u64> { (0..size).map(|_| rng.next_u64()).collect() };
+
+        ZobristKeys {
+            head: table(SNAKES * TILES),
+            body: table(SNAKES * TILES),
+            length: table(SNAKES * TILES),
+            health: table(SNAKES * HEALTHS),
+            food: table(TILES),
+            hazard: table(TILES * STACKS),
+            turn: table(TURNS),
+            to_reply: table(Direction::ALL.len()),
+        }
+    }
+
+    pub fn get() -> &'static ZobristKeys {
+        KEYS.get_or_init(ZobristKeys::new)
+    }
+
+    pub fn head(&self, id: u8, pos: Coordinate) -> u64 {
+        self.head[snake(id) * TILES + tile(pos)]
+    }
+
+    // A body segment is keyed by the turn it was the head, the turn less its place in the body,
+    // so the rest of the body keeps its keys as the snake moves
+    // There are too many turns to give each its own table, so the turn is mixed into the key
+    pub fn body(&self, id: u8, pos: Coordinate, turn: i32) -> u64 {
+        Rng::mix(self.body[snake(id) * TILES + tile(pos)] ^ turn as u32 as u64)
+    }
+
+    pub fn length(&self, id: u8, length: usize) -> u64 {
+        self.length[snake(id) * TILES + length % TILES]
+    }
+
+    // Healths in the same HEALTH_BUCKET share a key
+    pub fn health(&self, id: u8, health: i32) -> u64 {
+        let bucket = (health / HEALTH_BUCKET).clamp(0, HEALTHS as i32 - 1) as usize;
+        self.health[snake(id) * HEALTHS + bucket]
+    }
+
+    pub fn food(&self, pos: Coordinate) -> u64 {
+        self.food[tile(pos)]
+    }
+
+    // count is the number of hazards stacked on pos, each count has its own key so stacks don't cancel out
+    pub fn hazard(&self, pos: Coordinate, count: usize) -> u64 {
+        self.hazard[tile(pos) * STACKS + (count - 1) % STACKS]
+    }
+
+    pub fn turn(&self, turn: i32) -> u64 {
+        self.turn[turn.rem_euclid(TURNS as i32) as usize]
+    }
+
+    pub fn to_reply(&self, direction: Direction) -> u64 {
+        self.to_reply[direction.index()]
+    }
+
+    // Returns the keys of snake on a board at turn: its head, length, health bucket and body
+    pub fn snake(&self, snake: &Battlesnake, turn: i32) -> u64 {
+        let id = snake.get_id();
+        let mut hash = self.head(id, snake.get_head())
+            ^ self.length(id, snake.get_length())
+            ^ self.health(id, snake.get_health());
+
+        for (index, &pos) in snake.get_body().iter().enumerate().skip(1) {
+            hash ^= self.body(id, pos, turn - index as i32);
+        }
+
+        hash
+    }
+}
+
+impl Board {
+    // Returns the Zobrist hash of the position: snake bodies, health buckets, food, hazards and turn
+    // Royale shrinks and expected food spawns depend on the turn, and positions only transpose
+    // within the same turn anyway, so hashing it costs no reuse
+    // make_move keeps it up to date, boards changed any other way are hashed from scratch
+    pub fn zobrist_hash(&self) -> u64 {
+        if self.hash_valid {
+            self.hash
+        } else {
+            self.compute_zobrist_hash()
+        }
+    }
+
+    // Returns the hash of the position once I chose direction, while the other snakes have to reply
+    pub fn zobrist_hash_to_reply(&self, direction: Direction) -> u64 {
+        self.zobrist_hash() ^ ZobristKeys::get().to_reply(direction)
+    }
+
+    fn compute_zobrist_hash(&self) -> u64 {
+        let keys = ZobristKeys::get();
+        let mut hash = keys.turn(self.turn);
+
+        for snake in &self.snakes {
+            hash ^= keys.snake(snake, self.turn);
+        }
+
+        for &pos in &self.food {
+            hash ^= keys.food(pos);
+        }
+
+        // Hazards are kept sorted, so each stack is a run of equal positions
+        for stack in self.hazards.chunk_by(|a, b| a == b) {
+            hash ^= keys.hazard(stack[0], stack.len());
+        }
+
+        hash
+    }
+
+    // Hashes the position from scratch, make_move keeps it up to date from then on
+    pub fn rebuild_zobrist_hash(&mut self) {
+        self.hash = self.compute_zobrist_hash();
+        self.hash_valid = true;
+    }
+
+    // Marks the hash as out of date, it's rebuilt by the next make_move
+    pub fn invalidate_zobrist_hash(&mut self) {
+        self.hash_valid = false;
+    }
+
+    pub fn is_zobrist_hash_valid(&self) -> bool {
+        self.hash_valid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::_TEST_PATH;
+    use crate::coordinate::Coordinate;
+    use crate::direction::Direction;
+    use crate::load_object;
+
+    // zobrist_hash()
+    #[test]
+    fn test_zobrist_snake_order() {
+        let board = load_object!(Board, "test_board-03", _TEST_PATH);
+        let mut reversed = board.clone();
+        reversed.get_snakes_mut().reverse();
+
+        // The hash describes the position, not the order snakes are stored in
+        assert_eq!(board.zobrist_hash(), reversed.zobrist_hash());
+    }
+
+    #[test]
+    fn test_zobrist_move() {
+        let board = load_object!(Board, "test_board-03", _TEST_PATH);
+        let mut moved = board.clone();
+        let snake = &moved.get_snakes()[0];
+        let pos = snake.get_move(snake.get_options()[0]);
+        moved.get_snakes_mut()[0].move_to(pos);
+
+        assert_ne!(board.zobrist_hash(), moved.zobrist_hash());
+    }
+
+    // A tail stacked after eating must not cancel itself out of the hash
+    #[test]
+    fn test_zobrist_stacked_tail() {
+        let board = load_object!(Board, "test_board-03", _TEST_PATH);
+        let head = board.get_snakes()[0].get_head();
+        let neck = Coordinate::new(head.get_x(), head.get_y() - 1);
+        let stacked = |tail: Coordinate| {
+            let mut stacked = board.clone();
+            let body = stacked.get_snakes_mut()[0].get_body_mut();
+            body.clear();
+            body.extend([head, neck, tail, tail]);
+            stacked.zobrist_hash()
+        };
+
+        assert_ne!(
+            stacked(Coordinate::new(neck.get_x() - 1, neck.get_y())),
+            stacked(Coordinate::new(neck.get_x() + 1, neck.get_y()))
+        );
+    }
+
+    #[test]
+    fn test_zobrist_segment_order() {
+        let board = load_object!(Board, "test_board-03", _TEST_PATH);
+        let mut reordered = board.clone();
+        let body = reordered.get_snakes_mut()[0].get_body_mut();
+        let last = body.len() - 1;
+        body.swap(1, last);
+
+        // The same tiles in a different order are a different body
+        assert_ne!(board.zobrist_hash(), reordered.zobrist_hash());
+    }
+
+    // Two hazards on a tile must not cancel out, they do twice the damage of one
+    #[test]
+    fn test_zobrist_stacked_hazards() {
+        let board = load_object!(Board, "test_board-03", _TEST_PATH);
+        let pos = Coordinate::new(0, 0);
+        let hazards = |count: usize| {
+            let mut hazards = board.clone();
+            hazards.set_hazards(vec![pos; count]);
+            hazards.zobrist_hash()
+        };
+
+        assert_ne!(hazards(2), hazards(0));
+        assert_ne!(hazards(2), hazards(1));
+    }
+
+    #[test]
+    fn test_zobrist_turn() {
+        let board = load_object!(Board, "test_board-03", _TEST_PATH);
+        let mut later = board.clone();
+        later.set_turn(board.get_turn() + 1);
+
+        assert_ne!(board.zobrist_hash(), later.zobrist_hash());
+    }
+
+    #[test]
+    fn test_zobrist_food() {
+        let board = load_object!(Board, "eat-01-before", _TEST_PATH);
+        let mut no_food = board.clone();
+        no_food.get_food_mut().clear();
+
+        assert_ne!(board.zobrist_hash(), no_food.zobrist_hash());
+        assert_ne!(
+            board.zobrist_hash(),
+            board.zobrist_hash_to_reply(Direction::Down)
+        );
+    }
+}
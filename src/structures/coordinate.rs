@@ -1,4 +1,5 @@
 use crate::constants::DIRECTIONS;
+use crate::direction::Direction;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
@@ -35,6 +36,16 @@ impl Coordinate {
         ]
     }
 
+    // Return the tile one position in direction
+    pub fn get_neighbour(&self, direction: Direction) -> Coordinate {
+        match direction {
+            Direction::Down => self.get_down(),
+            Direction::Up => self.get_up(),
+            Direction::Right => self.get_right(),
+            Direction::Left => self.get_left(),
+        }
+    }
+
     // Return the tile one position down
     pub fn get_down(&self) -> Coordinate {
         Coordinate::new(self.x, self.y - 1)
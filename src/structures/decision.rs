@@ -0,0 +1,402 @@
+use crate::constants::DIRECTIONS;
+use crate::direction::Direction;
+
+// What calculate_move found out about moving in one direction
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DirectionFeatures {
+    // I survive the searched turns
+    pub survival: bool,
+    // The search result is one of the best
+    pub best: bool,
+    // There's a route at least as long as me, or a tail to chase
+    pub can_escape: bool,
+    // Not against a wall, unless there's food there
+    pub away_from_wall: bool,
+    // Closer to the closest food, only while food is worth going for
+    pub towards_food: bool,
+    // Controls the most tiles of any direction
+    pub max_control: bool,
+    // Has the longest route of any direction
+    pub max_area: bool,
+}
+
+// Everything calculate_move decides with
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Features {
+    // The best search result eliminates another snake
+    pub will_kill: bool,
+    // Indexed by Direction
+    pub directions: [DirectionFeatures; DIRECTIONS + 1],
+}
+
+// A named condition for picking a direction
+pub struct Rule {
+    pub name: &'static str,
+    pub applies: fn(&Features, &DirectionFeatures) -> bool,
+}
+
+/*
+Rules in priority order, the first rule that applies to any direction picks it
+Directions are tried in order: Down, up, right, left
+*/
+pub const RULES: [Rule; 13] = [
+    // Move towards kill with best move
+    Rule {
+        name: "kill",
+        applies: |features, d| d.survival && features.will_kill && d.best,
+    },
+    // Move towards closest food with best move avoiding walls
+    Rule {
+        name: "food_away_from_walls",
+        applies: |_, d| d.survival && d.can_escape && d.best && d.away_from_wall && d.towards_food,
+    },
+    // Move towards largest area of control with best move avoiding walls
+    Rule {
+        name: "control_away_from_walls",
+        applies: |_, d| d.survival && d.can_escape && d.best && d.max_control && d.away_from_wall,
+    },
+    // Move towards escape with best move avoiding walls
+    Rule {
+        name: "escape_away_from_walls",
+        applies: |_, d| d.survival && d.can_escape && d.best && d.away_from_wall,
+    },
+    // Move towards closest food with best move
+    Rule {
+        name: "food",
+        applies: |_, d| d.survival && d.can_escape && d.best && d.towards_food,
+    },
+    // Move towards largest area of control with best move
+    Rule {
+        name: "control",
+        applies: |_, d| d.survival && d.can_escape && d.best && d.max_control,
+    },
+    // Move towards escape with best move
+    Rule {
+        name: "escape",
+        applies: |_, d| d.survival && d.can_escape && d.best,
+    },
+    // Move towards escape with largest area of control
+    Rule {
+        name: "survive_with_control",
+        applies: |_, d| d.survival && d.can_escape && d.max_control,
+    },
+    // Move towards escape
+    Rule {
+        name: "survive",
+        applies: |_, d| d.survival && d.can_escape,
+    },
+    // Move towards best move, can escape
+    Rule {
+        name: "escape_without_survival",
+        applies: |_, d| d.can_escape && d.best,
+    },
+    // Move towards escape with largest area of control
+    Rule {
+        name: "control_without_survival",
+        applies: |_, d| d.can_escape && d.max_control,
+    },
+    // Go for best move with most turns, no survival
+    Rule {
+        name: "longest_best",
+        applies: |_, d| d.best && d.max_area,
+    },
+    // Go for most turns, no survival
+    Rule {
+        name: "longest",
+        applies: |_, d| d.max_area,
+    },
+];
+
+// Name of the decision when no rule applies
+pub const DEFAULT_RULE: &str = "default";
+
+// Returns the direction picked by the first rule that applies, and the name of that rule
+// Goes up when no rule applies
+pub fn decide(features: &Features) -> (Direction, &'static str) {
+    for rule in &RULES {
+        for direction in Direction::ALL {
+            if (rule.applies)(features, &features.directions[direction.index()]) {
+                return (direction, rule.name);
+            }
+        }
+    }
+
+    (Direction::Up, DEFAULT_RULE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::_TEST_PATH;
+    use crate::load_object;
+    use crate::move_request::MoveRequest;
+
+    // A direction every rule applies to
+    const EVERYTHING: DirectionFeatures = DirectionFeatures {
+        survival: true,
+        best: true,
+        can_escape: true,
+        away_from_wall: true,
+        towards_food: true,
+        max_control: true,
+        max_area: true,
+    };
+
+    fn features(direction: Direction, features: DirectionFeatures) -> Features {
+        let mut result = Features::default();
+        result.directions[direction.index()] = features;
+        result
+    }
+
+    // Only direction has features, so the first rule applying to it decides
+    fn assert_rule(
+        direction: Direction,
+        features_of: DirectionFeatures,
+        will_kill: bool,
+        rule: &str,
+    ) {
+        let mut features = features(direction, features_of);
+        features.will_kill = will_kill;
+
+        assert_eq!(decide(&features), (direction, rule));
+    }
+
+    // Decides the move on the fixture for rule, which should be decided by that rule
+    // Exactly three levels are searched with no time limit, so the features don't depend on how fast the tests run
+    fn assert_fixture_rule(direction: Direction, rule: &str) {
+        let data = load_object!(MoveRequest, format!("decide_{}-01", rule), _TEST_PATH);
+
+        let values = data.into_values();
+        let board = values.2.into_board(values.3, values.1);
+        let mut game = values.0.into_game();
+        game.set_max_depth(3);
+        game.set_fixed_depth(true);
+
+        assert_eq!(game.decide_move(board), (direction, rule));
+    }
+
+    #[test]
+    fn test_rule_names_unique() {
+        for (i, rule) in RULES.iter().enumerate() {
+            assert!(rule.name != DEFAULT_RULE);
+            assert!(RULES[i + 1..].iter().all(|other| other.name != rule.name));
+        }
+    }
+
+    #[test]
+    fn test_kill() {
+        assert_rule(Direction::Right, EVERYTHING, true, "kill");
+    }
+
+    #[test]
+    fn test_food_away_from_walls() {
+        assert_rule(Direction::Up, EVERYTHING, false, "food_away_from_walls");
+    }
+
+    #[test]
+    fn test_control_away_from_walls() {
+        let features = DirectionFeatures {
+            towards_food: false,
+            ..EVERYTHING
+        };
+        assert_rule(Direction::Left, features, false, "control_away_from_walls");
+    }
+
+    #[test]
+    fn test_escape_away_from_walls() {
+        let features = DirectionFeatures {
+            towards_food: false,
+            max_control: false,
+            ..EVERYTHING
+        };
+        assert_rule(Direction::Down, features, false, "escape_away_from_walls");
+    }
+
+    #[test]
+    fn test_food() {
+        let features = DirectionFeatures {
+            away_from_wall: false,
+            ..EVERYTHING
+        };
+        assert_rule(Direction::Up, features, false, "food");
+    }
+
+    #[test]
+    fn test_control() {
+        let features = DirectionFeatures {
+            away_from_wall: false,
+            towards_food: false,
+            ..EVERYTHING
+        };
+        assert_rule(Direction::Right, features, false, "control");
+    }
+
+    #[test]
+    fn test_escape() {
+        let features = DirectionFeatures {
+            survival: true,
+            best: true,
+            can_escape: true,
+            ..DirectionFeatures::default()
+        };
+        assert_rule(Direction::Left, features, false, "escape");
+    }
+
+    #[test]
+    fn test_survive_with_control() {
+        let features = DirectionFeatures {
+            best: false,
+            ..EVERYTHING
+        };
+        assert_rule(Direction::Down, features, false, "survive_with_control");
+    }
+
+    #[test]
+    fn test_survive() {
+        let features = DirectionFeatures {
+            survival: true,
+            can_escape: true,
+            ..DirectionFeatures::default()
+        };
+        assert_rule(Direction::Up, features, false, "survive");
+    }
+
+    #[test]
+    fn test_escape_without_survival() {
+        let features = DirectionFeatures {
+            survival: false,
+            ..EVERYTHING
+        };
+        assert_rule(Direction::Right, features, true, "escape_without_survival");
+    }
+
+    #[test]
+    fn test_control_without_survival() {
+        let features = DirectionFeatures {
+            survival: false,
+            best: false,
+            ..EVERYTHING
+        };
+        assert_rule(Direction::Left, features, false, "control_without_survival");
+    }
+
+    #[test]
+    fn test_longest_best() {
+        let features = DirectionFeatures {
+            best: true,
+            max_area: true,
+            ..DirectionFeatures::default()
+        };
+        assert_rule(Direction::Down, features, false, "longest_best");
+    }
+
+    #[test]
+    fn test_longest() {
+        let features = DirectionFeatures {
+            max_area: true,
+            ..DirectionFeatures::default()
+        };
+        assert_rule(Direction::Right, features, false, "longest");
+    }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(decide(&Features::default()), (Direction::Up, DEFAULT_RULE));
+    }
+
+    // Up used to need down to survive before going for food
+    #[test]
+    fn test_food_up_needs_up_survival() {
+        let mut features = features(
+            Direction::Up,
+            DirectionFeatures {
+                survival: false,
+                ..EVERYTHING
+            },
+        );
+        features.directions[Direction::Down.index()].survival = true;
+
+        assert_eq!(
+            decide(&features),
+            (Direction::Up, "escape_without_survival")
+        );
+    }
+
+    // Earlier rules win over directions that come first
+    #[test]
+    fn test_rule_order() {
+        let mut features = features(Direction::Left, EVERYTHING);
+        features.directions[Direction::Down.index()] = DirectionFeatures {
+            max_area: true,
+            ..DirectionFeatures::default()
+        };
+
+        assert_eq!(decide(&features), (Direction::Left, "food_away_from_walls"));
+    }
+
+    // Boards each rule decides on, so the features decide_move finds are checked too
+    #[test]
+    fn test_kill_fixture() {
+        assert_fixture_rule(Direction::Up, "kill");
+    }
+
+    #[test]
+    fn test_food_away_from_walls_fixture() {
+        assert_fixture_rule(Direction::Up, "food_away_from_walls");
+    }
+
+    #[test]
+    fn test_control_away_from_walls_fixture() {
+        assert_fixture_rule(Direction::Down, "control_away_from_walls");
+    }
+
+    #[test]
+    fn test_escape_away_from_walls_fixture() {
+        assert_fixture_rule(Direction::Down, "escape_away_from_walls");
+    }
+
+    #[test]
+    fn test_food_fixture() {
+        assert_fixture_rule(Direction::Up, "food");
+    }
+
+    #[test]
+    fn test_control_fixture() {
+        assert_fixture_rule(Direction::Right, "control");
+    }
+
+    #[test]
+    fn test_escape_fixture() {
+        assert_fixture_rule(Direction::Right, "escape");
+    }
+
+    #[test]
+    fn test_survive_with_control_fixture() {
+        assert_fixture_rule(Direction::Right, "survive_with_control");
+    }
+
+    #[test]
+    fn test_survive_fixture() {
+        assert_fixture_rule(Direction::Right, "survive");
+    }
+
+    #[test]
+    fn test_escape_without_survival_fixture() {
+        assert_fixture_rule(Direction::Right, "escape_without_survival");
+    }
+
+    #[test]
+    fn test_control_without_survival_fixture() {
+        assert_fixture_rule(Direction::Right, "control_without_survival");
+    }
+
+    #[test]
+    fn test_longest_best_fixture() {
+        assert_fixture_rule(Direction::Down, "longest_best");
+    }
+
+    #[test]
+    fn test_longest_fixture() {
+        assert_fixture_rule(Direction::Right, "longest");
+    }
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::constants::DIRECTIONS;
+
+// A move on the board, serialized as the API string
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Down = 0,
+    Up = 1,
+    Right = 2,
+    Left = 3,
+}
+
+impl Direction {
+    // All directions in order: Down, up, right, left
+    pub const ALL: [Direction; DIRECTIONS + 1] = [
+        Direction::Down,
+        Direction::Up,
+        Direction::Right,
+        Direction::Left,
+    ];
+
+    // Returns the position of self in Direction::ALL
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Down => Direction::Up,
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    // Returns the directions a snake can move with its neck in direction neck
+    // A relative move is an index into this array, used to enumerate moves in the search
+    pub fn relative_to(neck: Direction) -> [Direction; DIRECTIONS] {
+        let mut options = [Direction::Down; DIRECTIONS];
+        for (option, direction) in options.iter_mut().zip(
+            Direction::ALL
+                .into_iter()
+                .filter(|&direction| direction != neck),
+        ) {
+            *option = direction;
+        }
+        options
+    }
+
+    // The string used by the Battlesnake API
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Direction::Down => "down",
+            Direction::Up => "up",
+            Direction::Right => "right",
+            Direction::Left => "left",
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_to() {
+        let options = Direction::relative_to(Direction::Right);
+
+        assert_eq!(options, [Direction::Down, Direction::Up, Direction::Left]);
+    }
+
+    #[test]
+    fn test_serialize() {
+        let json = serde_json::to_string(&Direction::Left).unwrap();
+
+        assert_eq!(json, "\"left\"");
+        assert_eq!(
+            serde_json::from_str::<Direction>(&json).unwrap(),
+            Direction::Left
+        );
+    }
+}
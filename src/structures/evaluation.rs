@@ -0,0 +1,267 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::config::Weights;
+use crate::constants::SURVIVAL_PROBABILITY;
+
+// The score of one snake on a board, from Board::evaluate
+// Default is the score of a dead snake, worse than any living one
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Evaluation {
+    alive: bool,
+    // Weighted sum of the parts below, 0 until weighted
+    score: i64,
+    // Snakes competing with me, including myself
+    competitors: usize,
+    // At least two open directions
+    open: bool,
+    // 100 minus the distance to the closest weaker snake, 0 without one
+    weak_snake: i32,
+    length: usize,
+    // 100 minus the distance to the closest food, 0 without food
+    food: i32,
+    // Tiles controlled, only scored on constrictor boards
+    area: i32,
+    // Open directions that don't lead into a damaging hazard
+    safe_directions: i32,
+}
+
+impl Evaluation {
+    // The score of a living snake
+    pub fn new(
+        competitors: usize,
+        open: bool,
+        weak_snake: i32,
+        length: usize,
+        food: i32,
+        area: i32,
+        safe_directions: i32,
+    ) -> Evaluation {
+        Evaluation {
+            alive: true,
+            score: 0,
+            competitors,
+            open,
+            weak_snake,
+            length,
+            food,
+            area,
+            safe_directions,
+        }
+    }
+
+    // Better than any score, the starting bound of a minimising search
+    pub const MAX: Evaluation = Evaluation {
+        alive: true,
+        score: i64::MAX,
+        competitors: 0,
+        open: true,
+        weak_snake: i32::MAX,
+        length: usize::MAX,
+        food: i32::MAX,
+        area: i32::MAX,
+        safe_directions: i32::MAX,
+    };
+
+    // Worse than or equal to any score, the starting bound of a maximising search
+    pub const MIN: Evaluation = Evaluation {
+        alive: false,
+        score: i64::MIN,
+        competitors: 0,
+        open: false,
+        weak_snake: 0,
+        length: 0,
+        food: 0,
+        area: 0,
+        safe_directions: 0,
+    };
+
+    // Returns self scored with weights
+    pub fn weighted(mut self, weights: &Weights) -> Evaluation {
+        self.score = weights.get_competitors() * self.competitors as i64
+            + weights.get_open() * self.open as i64
+            + weights.get_weak_snake() * self.weak_snake as i64
+            + weights.get_length() * self.length as i64
+            + weights.get_area() * self.area as i64
+            + weights.get_food() * self.food as i64
+            + weights.get_safe_directions() * self.safe_directions as i64;
+        self
+    }
+
+    // Returns the outcome expected from outcomes, each reached with a probability
+    // I count as alive if I survive with at least SURVIVAL_PROBABILITY, dead outcomes score weights' death
+    // The other parts are copied from the most likely outcome that agrees on being alive
+    pub fn expected(outcomes: &[(f64, Evaluation)], weights: &Weights) -> Evaluation {
+        let total: f64 = outcomes.iter().map(|(probability, _)| probability).sum();
+        if total <= 0.0 {
+            return Evaluation::default();
+        }
+
+        let mut survival = 0.0;
+        let mut score = 0.0;
+        for (probability, outcome) in outcomes {
+            let probability = probability / total;
+            if outcome.alive {
+                survival += probability;
+                score += probability * outcome.score as f64;
+            } else {
+                score += probability * weights.get_death() as f64;
+            }
+        }
+
+        let alive = survival >= SURVIVAL_PROBABILITY;
+        let mut result = outcomes
+            .iter()
+            .filter(|(_, outcome)| outcome.alive == alive)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|&(_, outcome)| outcome)
+            .unwrap_or_default();
+        result.alive = alive;
+        result.score = score.round() as i64;
+        result
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    pub fn get_score(&self) -> i64 {
+        self.score
+    }
+
+    pub fn get_competitors(&self) -> usize {
+        self.competitors
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn get_weak_snake(&self) -> i32 {
+        self.weak_snake
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    pub fn get_food(&self) -> i32 {
+        self.food
+    }
+
+    pub fn get_area(&self) -> i32 {
+        self.area
+    }
+
+    pub fn get_safe_directions(&self) -> i32 {
+        self.safe_directions
+    }
+}
+
+/*
+Living snakes beat dead ones, then higher weighted scores win
+Equal scores are compared lexicographically, each part only counts when all before it are equal:
+    Fewer competitors
+    Open
+    Closer to a weaker snake
+    Longer
+    More area, then closer to food
+    More safe directions
+*/
+impl Ord for Evaluation {
+    fn cmp(&self, other: &Evaluation) -> Ordering {
+        self.alive
+            .cmp(&other.alive)
+            .then(self.score.cmp(&other.score))
+            .then(other.competitors.cmp(&self.competitors))
+            .then(self.open.cmp(&other.open))
+            .then(self.weak_snake.cmp(&other.weak_snake))
+            .then(self.length.cmp(&other.length))
+            .then(self.area.cmp(&other.area))
+            .then(self.food.cmp(&other.food))
+            .then(self.safe_directions.cmp(&other.safe_directions))
+    }
+}
+
+impl PartialOrd for Evaluation {
+    fn partial_cmp(&self, other: &Evaluation) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Written as JSON for the logs
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            serde_json::to_string(self).map_err(|_| fmt::Error)?
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dead() {
+        let alive = Evaluation::new(8, false, 0, 1, 0, 0, 0);
+
+        assert!(Evaluation::default() < alive);
+        assert!(Evaluation::MIN <= Evaluation::default());
+        assert!(alive < Evaluation::MAX);
+    }
+
+    #[test]
+    fn test_lexicographic() {
+        let fewer_competitors = Evaluation::new(2, false, 0, 3, 0, 0, 0);
+        let longer = Evaluation::new(3, true, 0, 30, 90, 0, 4);
+
+        let food = Evaluation::new(2, true, 0, 4, 99, 0, 4);
+
+        assert!(fewer_competitors > longer);
+        assert!(Evaluation::new(2, true, 0, 5, 0, 0, 0) > food);
+    }
+
+    #[test]
+    fn test_weighted() {
+        let weights = Weights::default();
+        let hungry = Evaluation::new(2, true, 0, 4, 99, 0, 4).weighted(&weights);
+        let long = Evaluation::new(2, true, 0, 5, 0, 0, 0).weighted(&weights);
+
+        assert_eq!(long.get_score(), -2_000_000_000 + 100_000_000 + 5_000);
+        assert!(long > hungry);
+    }
+
+    #[test]
+    fn test_expected() {
+        let weights = Weights::default();
+        let alive = Evaluation::new(2, true, 0, 5, 0, 0, 0).weighted(&weights);
+        let likely = Evaluation::expected(&[(0.9, alive), (0.1, Evaluation::default())], &weights);
+        let unlikely =
+            Evaluation::expected(&[(0.5, alive), (0.5, Evaluation::default())], &weights);
+
+        assert!(likely.is_alive());
+        assert_eq!(likely.get_length(), 5);
+        assert_eq!(
+            likely.get_score(),
+            (0.9 * alive.get_score() as f64 + 0.1 * weights.get_death() as f64).round() as i64
+        );
+        assert!(!unlikely.is_alive());
+        assert!(likely > unlikely);
+        // Certain outcomes are unchanged
+        assert_eq!(Evaluation::expected(&[(1.0, alive)], &weights), alive);
+    }
+
+    #[test]
+    fn test_serialize() {
+        let evaluation = Evaluation::new(2, true, 0, 5, 95, 0, 3);
+
+        assert_eq!(
+            evaluation.to_string(),
+            "{\"alive\":true,\"score\":0,\"competitors\":2,\"open\":true,\"weak_snake\":0,\"length\":5,\"food\":95,\"area\":0,\"safe_directions\":3}"
+        );
+    }
+}
@@ -1,19 +1,65 @@
+use rayon::prelude::*;
+use rayon::ThreadPool;
 use serde::{Deserialize, Serialize};
-use std::cmp::{max, min, Ordering};
+use std::cmp::{max, min};
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::sync::mpsc;
-use std::thread::spawn;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use crate::board::royale_shrink::RoyaleShrink;
+use crate::board::simulate::SearchMode;
+use crate::board::spawn_food::FoodSpawn;
 use crate::board::Board;
-use crate::constants::{EXPONENT, LENGTH_ADVANTAGE, LOG_LEVEL, LOG_PATH, MAX_SEARCH, YOU_ID};
+use crate::config::{Config, Profile};
+use crate::constants::{
+    DIRECTIONS, LOG_LEVEL, LOG_PATH, MAX_DEPTH, MCTS_ITERATIONS, SEARCH_MODE, SPAWN_FOOD,
+    TABLE_SIZE, YOU_ID,
+};
+use crate::decision::{decide, DirectionFeatures, Features};
+use crate::direction::Direction;
+use crate::evaluation::Evaluation;
+use crate::opponent_model::OpponentModel;
 use crate::ruleset::Ruleset;
+use crate::transposition_table::TranspositionTable;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Game {
     id: String,
     ruleset: Ruleset,
     timeout: i32,
+    #[serde(skip)]
+    search_mode: SearchMode,
+    // Long-lived pool the search runs on, the global rayon pool is used without one
+    #[serde(skip)]
+    thread_pool: Option<Arc<ThreadPool>>,
+    // Kept between turns to warm start the search, a new table is used without one
+    #[serde(skip)]
+    table: Option<Arc<TranspositionTable>>,
+    // The board the table was last searched from
+    #[serde(skip)]
+    previous_board: Option<Board>,
+    // What the opponents were seen doing, used by the expected search
+    #[serde(skip)]
+    opponent_model: OpponentModel,
+    // Tuning for this game, the config in use without one
+    #[serde(skip)]
+    config: Option<Arc<Config>>,
+    // Deepest level iterative deepening searches to
+    #[serde(skip, default = "default_max_depth")]
+    max_depth: i32,
+    // Searches to max_depth without a time limit, so a position always gets the same move
+    // Monte Carlo tree search runs MCTS_ITERATIONS instead
+    #[serde(skip)]
+    fixed_depth: bool,
+    // Snakes the game started with, which picks the profile
+    // Without it the snakes left on the board are counted
+    #[serde(skip)]
+    starting_snakes: Option<usize>,
+}
+
+fn default_max_depth() -> i32 {
+    MAX_DEPTH
 }
 
 impl Game {
@@ -22,512 +68,195 @@ impl Game {
             id,
             ruleset,
             timeout,
+            search_mode: SEARCH_MODE,
+            thread_pool: None,
+            table: None,
+            previous_board: None,
+            opponent_model: OpponentModel::default(),
+            config: None,
+            max_depth: MAX_DEPTH,
+            fixed_depth: false,
+            starting_snakes: None,
         }
     }
 
-    // Returns the direction to go based on the game board
-    pub fn calculate_move(&self, board: Board) -> String {
-        // Calculate max recursion depth
-        let max_depth = max(EXPONENT / board.get_snakes().len() as i32, 1);
-
-        // Predict future turns
-        // Create a thread for down
-        let down_board = board.clone();
-        let down_ruleset = self.ruleset.clone();
-        let (down_tx, down_rx) = mpsc::channel();
-        let down_handle = spawn(move || {
-            let down = down_board.check_down(&down_ruleset, 0, max_depth);
-            down_tx.send(down).unwrap();
-        });
-
-        // Create a thread for up
-        let up_board = board.clone();
-        let up_ruleset = self.ruleset.clone();
-        let (up_tx, up_rx) = mpsc::channel();
-        let up_handle = spawn(move || {
-            let up = up_board.check_up(&up_ruleset, 0, max_depth);
-            up_tx.send(up).unwrap();
-        });
+    pub fn get_ruleset(&self) -> &Ruleset {
+        &self.ruleset
+    }
 
-        // Create a thread for right
-        let right_board = board.clone();
-        let right_ruleset = self.ruleset.clone();
-        let (right_tx, right_rx) = mpsc::channel();
-        let right_handle = spawn(move || {
-            let right = right_board.check_right(&right_ruleset, 0, max_depth);
-            right_tx.send(right).unwrap();
-        });
+    pub fn get_search_mode(&self) -> SearchMode {
+        self.search_mode
+    }
 
-        // Create a thread for left
-        let left_board = board.clone();
-        let left_ruleset = self.ruleset.clone();
-        let (left_tx, left_rx) = mpsc::channel();
-        let left_handle = spawn(move || {
-            let left = left_board.check_left(&left_ruleset, 0, max_depth);
-            left_tx.send(left).unwrap();
-        });
+    pub fn set_search_mode(&mut self, search_mode: SearchMode) {
+        self.search_mode = search_mode;
+    }
 
-        // About myself
-        let you = &board.get_snakes()[0];
-        let current_pos = you.get_head();
-        let down_pos = current_pos.get_down();
-        let up_pos = current_pos.get_up();
-        let right_pos = current_pos.get_right();
-        let left_pos = current_pos.get_left();
-
-        let max_search = min(you.get_length() as i32, MAX_SEARCH);
-
-        // Check area I can move in each direction
-        // Check down area
-        let down_area_board = board.clone();
-        let (down_area_tx, down_area_rx) = mpsc::channel();
-        let down_area_handle = spawn(move || {
-            let down_area = down_area_board.longest_path(
-                current_pos.get_down(),
-                0,
-                max_search,
-                &mut Vec::with_capacity(max_search as usize),
-                0,
-            );
-            down_area_tx.send(down_area).unwrap();
-        });
+    pub fn set_thread_pool(&mut self, thread_pool: Arc<ThreadPool>) {
+        self.thread_pool = Some(thread_pool);
+    }
 
-        // Check up area
-        let up_area_board = board.clone();
-        let (up_area_tx, up_area_rx) = mpsc::channel();
-        let up_area_handle = spawn(move || {
-            let up_area = up_area_board.longest_path(
-                current_pos.get_up(),
-                0,
-                max_search,
-                &mut Vec::with_capacity(max_search as usize),
-                0,
-            );
-            up_area_tx.send(up_area).unwrap();
-        });
+    pub fn set_table(&mut self, table: Arc<TranspositionTable>) {
+        self.table = Some(table);
+    }
 
-        // Check right area
-        let right_area_board = board.clone();
-        let (right_area_tx, right_area_rx) = mpsc::channel();
-        let right_area_handle = spawn(move || {
-            let right_area = right_area_board.longest_path(
-                current_pos.get_right(),
-                0,
-                max_search,
-                &mut Vec::with_capacity(max_search as usize),
-                0,
-            );
-            right_area_tx.send(right_area).unwrap();
-        });
+    pub fn set_previous_board(&mut self, previous_board: Board) {
+        self.previous_board = Some(previous_board);
+    }
 
-        // Check left area
-        let left_area_board = board.clone();
-        let (left_area_tx, left_area_rx) = mpsc::channel();
-        let left_area_handle = spawn(move || {
-            let left_area = left_area_board.longest_path(
-                current_pos.get_left(),
-                0,
-                max_search,
-                &mut Vec::with_capacity(max_search as usize),
-                0,
-            );
-            left_area_tx.send(left_area).unwrap();
-        });
+    pub fn set_opponent_model(&mut self, opponent_model: OpponentModel) {
+        self.opponent_model = opponent_model;
+    }
 
-        // Find closest food
-        let closest_food = board.find_closest_food(current_pos);
-        let closest_food_exists = closest_food.is_some()
-            && board.get_snakes().iter().any(|snake| {
-                snake.get_id() != YOU_ID
-                    && snake.get_length() as i32 > you.get_length() as i32 - LENGTH_ADVANTAGE
-            });
-        let closest_food = closest_food.unwrap_or_default();
+    pub fn get_config(&self) -> &Config {
+        match &self.config {
+            Some(config) => config,
+            None => Config::get(),
+        }
+    }
 
-        // Find closest weak snake
-        let weak_snake_head = board.find_weaker_snake(you, LENGTH_ADVANTAGE);
-        let _weak_snake_exists = weak_snake_head.is_some();
-        let _weak_snake_head = weak_snake_head.unwrap_or_default();
+    pub fn set_config(&mut self, config: Arc<Config>) {
+        self.config = Some(config);
+    }
 
-        // Check if positions are against the wall or if they contain food
-        let down_wall = !board.is_against_wall(down_pos) || board.get_food().contains(&down_pos);
-        let up_wall = !board.is_against_wall(up_pos) || board.get_food().contains(&up_pos);
-        let right_wall = !board.is_against_wall(right_pos) || board.is_against_wall(right_pos);
-        let left_wall = !board.is_against_wall(left_pos) || board.get_food().contains(&left_pos);
+    pub fn get_max_depth(&self) -> i32 {
+        self.max_depth
+    }
 
-        // Find best area controls
-        let control_areas = board.calculate_areas(&self.ruleset);
-        let max_control = *control_areas.iter().max().unwrap();
-        let [down_control, up_control, right_control, left_control] = control_areas;
+    pub fn set_max_depth(&mut self, max_depth: i32) {
+        self.max_depth = max_depth;
+    }
 
-        // Finish down thread
-        let down_board = down_rx.recv().unwrap();
-        down_handle.join().unwrap();
+    pub fn is_fixed_depth(&self) -> bool {
+        self.fixed_depth
+    }
 
-        // Finish up thread
-        let up_board = up_rx.recv().unwrap();
-        up_handle.join().unwrap();
+    pub fn set_fixed_depth(&mut self, fixed_depth: bool) {
+        self.fixed_depth = fixed_depth;
+    }
 
-        // Finish right thread
-        let right_board = right_rx.recv().unwrap();
-        right_handle.join().unwrap();
+    pub fn set_starting_snakes(&mut self, starting_snakes: usize) {
+        self.starting_snakes = Some(starting_snakes);
+    }
 
-        // Finish left thread
-        let left_board = left_rx.recv().unwrap();
-        left_handle.join().unwrap();
+    // Returns the profile for this game played on board
+    fn get_profile(&self, board: &Board) -> &Profile {
+        let num_snakes = self
+            .starting_snakes
+            .unwrap_or_else(|| board.get_max_snakes());
+        self.get_config().get_profile(&self.ruleset, num_snakes)
+    }
 
-        // Find the best directions
-        let mut best_boards = Vec::with_capacity(4);
-        best_boards.push(&down_board);
+    // Returns the direction to go based on the game board
+    pub fn calculate_move(&self, board: Board) -> Direction {
+        self.decide_move(board).0
+    }
 
-        match up_board.cmp(&best_boards[0]) {
-            Ordering::Greater => {
-                best_boards.clear();
-                best_boards.push(&up_board);
-            }
-            Ordering::Equal => best_boards.push(&up_board),
-            Ordering::Less => {}
+    // Returns the direction to go and the name of the rule that picked it
+    pub fn decide_move(&self, mut board: Board) -> (Direction, &'static str) {
+        let start = Instant::now();
+        board.set_wrapped(self.ruleset.is_wrapped());
+        // Which side shrinks next can't be known, so the search covers every side
+        board.set_royale_shrink(RoyaleShrink::Projected);
+        if SPAWN_FOOD {
+            board.set_food_spawn(FoodSpawn::Expected);
         }
 
-        match right_board.cmp(&best_boards[0]) {
-            Ordering::Greater => {
-                best_boards.clear();
-                best_boards.push(&right_board);
-            }
-            Ordering::Equal => best_boards.push(&right_board),
-            Ordering::Less => {}
+        // Settings for this kind of game
+        let profile = self.get_profile(&board);
+        let deadline = if self.fixed_depth {
+            None
+        } else {
+            let latency = board.get_snakes()[YOU_ID as usize].get_latency();
+            Some(start + self.time_budget(profile, latency))
+        };
+
+        if self.search_mode == SearchMode::Mcts {
+            return (self.calculate_mcts_move(&board, start, deadline), "mcts");
         }
 
-        match left_board.cmp(&best_boards[0]) {
-            Ordering::Greater => {
-                best_boards.clear();
-                best_boards.push(&left_board);
-            }
-            Ordering::Equal => best_boards.push(&left_board),
-            Ordering::Less => {}
-        }
+        let length_advantage = profile.get_length_advantage();
 
-        // Store best directions
-        let down_best = best_boards.contains(&&down_board);
-        let up_best = best_boards.contains(&&up_board);
-        let right_best = best_boards.contains(&&right_board);
-        let left_best = best_boards.contains(&&left_board);
+        // About myself
+        let you = &board.get_snakes()[0];
+        let current_pos = you.get_head();
 
-        // True if I can survive
-        let down_survival = down_board > 0;
-        let up_survival = up_board > 0;
-        let right_survival = right_board > 0;
-        let left_survival = left_board > 0;
+        let health = you.get_health();
+        let max_search = min(you.get_length() as i32, profile.get_max_search());
 
-        // True if other snakes will die
-        let will_kill = 100 - ((best_boards[0] / 1_000_000_000) % 100)
-            < board.get_snakes().len() as u64;
+        // Find closest food
+        let closest_food = board.find_closest_food(current_pos);
+        let closest_food_exists = closest_food.is_some()
+            && board.get_snakes().iter().any(|snake| {
+                snake.get_id() != YOU_ID
+                    && snake.get_length() as i32 > you.get_length() as i32 - length_advantage
+            });
+        let closest_food = closest_food.unwrap_or_default();
 
-        // Finish down_area thread
-        let down_area = down_area_rx.recv().unwrap();
-        down_area_handle.join().unwrap();
-        let can_escape_down = down_area >= max_search;
+        // Find best area controls
+        let control_areas = board.calculate_areas(&self.ruleset);
+        let max_control = *control_areas.iter().max().unwrap();
+        let [down_control, up_control, right_control, left_control] = control_areas;
 
-        // Finish up_area thread
-        let up_area = up_area_rx.recv().unwrap();
-        up_area_handle.join().unwrap();
-        let can_escape_up = up_area >= max_search;
+        // Find the moves made since the last search, and how deep the position was searched then
+        let last_moves = match &self.previous_board {
+            Some(previous_board) => board.find_joint_move(previous_board),
+            None => Vec::new(),
+        };
+        let table = match &self.table {
+            Some(table) => Arc::clone(table),
+            None => Arc::new(TranspositionTable::new(TABLE_SIZE)),
+        };
+        table.new_search();
+        let reused_depth = table.get_depth(board.zobrist_hash()).unwrap_or(0);
+
+        // Predict future turns, searching deeper until the time budget is spent
+        // Positions found in earlier searches, this turn or the last, are shared through the table
+        // The area I can move in each direction is checked alongside
+        let (areas, (results, max_depth)) = self.run(|| {
+            rayon::join(
+                || self.escape_areas(&board, max_search, health),
+                || self.iterative_deepening(&board, &table, deadline),
+            )
+        });
+        let [down_board, up_board, right_board, left_board] = results;
 
-        // Finish right_area thread
-        let right_area = right_area_rx.recv().unwrap();
-        right_area_handle.join().unwrap();
-        let can_escape_right = right_area >= max_search;
+        // Find the best result
+        let best_result = *results.iter().max().unwrap();
 
-        // Finish left_area thread
-        let left_area = left_area_rx.recv().unwrap();
-        left_area_handle.join().unwrap();
-        let can_escape_left = left_area >= max_search;
+        // True if other snakes will die
+        let will_kill =
+            best_result.is_alive() && best_result.get_competitors() < board.count_competitors(you);
 
-        // Find max area available
-        let max_area = max(max(down_area, up_area), max(right_area, left_area));
+        // Check if I can escape in each direction
+        let [down_area, up_area, right_area, left_area] = areas;
+        let max_area = *areas.iter().max().unwrap();
 
-        // Take all data and decide on direction
-        let outcome;
-        let direction;
-
-        // Move towards kill with best move
-        if down_survival && will_kill && down_best {
-            outcome = 0;
-            direction = String::from("down");
-        } else if up_survival && will_kill && up_best {
-            outcome = 1;
-            direction = String::from("up");
-        } else if right_survival && will_kill && right_best {
-            outcome = 2;
-            direction = String::from("right");
-        } else if left_survival && will_kill && left_best {
-            outcome = 3;
-            direction = String::from("left");
-        }
-        // Move towards closest food with best move avoiding walls
-        else if down_survival
-            && can_escape_down
-            && down_best
-            && down_wall
-            && closest_food_exists
-            && closest_food.get_y() < current_pos.get_y()
-        {
-            outcome = 4;
-            direction = String::from("down");
-        } else if down_survival
-            && can_escape_up
-            && up_best
-            && up_wall
-            && closest_food_exists
-            && closest_food.get_y() > current_pos.get_y()
-        {
-            outcome = 5;
-            direction = String::from("up");
-        } else if right_survival
-            && can_escape_right
-            && right_best
-            && right_wall
-            && closest_food_exists
-            && closest_food.get_x() > current_pos.get_x()
-        {
-            outcome = 6;
-            direction = String::from("right");
-        } else if left_survival
-            && can_escape_left
-            && left_best
-            && left_wall
-            && closest_food_exists
-            && closest_food.get_x() < current_pos.get_x()
-        {
-            outcome = 7;
-            direction = String::from("left");
-        } 
-        // Move towards largest area of control with best move avoiding walls
-        else if down_survival
-            && can_escape_down
-            && down_best
-            && down_control == max_control
-            && down_wall
-        {
-            outcome = 8;
-            direction = String::from("down");
-        } else if up_survival
-            && can_escape_up
-            && up_best
-            && up_control == max_control
-            && up_wall
-        {
-            outcome = 9;
-            direction = String::from("up");
-        } else if right_survival
-            && can_escape_right
-            && right_best
-            && right_control == max_control
-            && right_wall
-        {
-            outcome = 10;
-            direction = String::from("right");
-        } else if left_survival
-            && can_escape_left
-            && left_best
-            && left_control == max_control
-            && left_wall
-        {
-            outcome = 11;
-            direction = String::from("left");
-        }
-        // Move towards escape with best move avoiding walls
-        else if down_survival && can_escape_down && down_best && down_wall
-        {
-            outcome = 12;
-            direction = String::from("down");
-        } else if up_survival && can_escape_up && up_best && up_wall {
-            outcome = 13;
-            direction = String::from("up");
-        } else if right_survival
-            && can_escape_right
-            && right_best
-            && right_wall
-        {
-            outcome = 14;
-            direction = String::from("right");
-        } else if left_survival && can_escape_left && left_best && left_wall
-        {
-            outcome = 15;
-            direction = String::from("left");
-        }
-        // Move towards closest food with best move
-        else if down_survival
-            && can_escape_down
-            && down_best
-            && closest_food_exists
-            && closest_food.get_y() < current_pos.get_y()
-        {
-            outcome = 16;
-            direction = String::from("down");
-        } else if down_survival
-            && can_escape_up
-            && up_best
-            && closest_food_exists
-            && closest_food.get_y() > current_pos.get_y()
-        {
-            outcome = 17;
-            direction = String::from("up");
-        } else if right_survival
-            && can_escape_right
-            && right_best
-            && closest_food_exists
-            && closest_food.get_x() > current_pos.get_x()
-        {
-            outcome = 18;
-            direction = String::from("right");
-        } else if left_survival
-            && can_escape_left
-            && left_best
-            && closest_food_exists
-            && closest_food.get_x() < current_pos.get_x()
-        {
-            outcome = 19;
-            direction = String::from("left");
-        } 
-        // Move towards largest area of control with best move
-        else if down_survival
-            && can_escape_down
-            && down_best
-            && down_control == max_control
-        {
-            outcome = 20;
-            direction = String::from("down");
-        } else if up_survival
-            && can_escape_up
-            && up_best
-            && up_control == max_control
-        {
-            outcome = 21;
-            direction = String::from("up");
-        } else if right_survival
-            && can_escape_right
-            && right_best
-            && right_control == max_control
-        {
-            outcome = 22;
-            direction = String::from("right");
-        } else if left_survival
-            && can_escape_left
-            && left_best
-            && left_control == max_control
-        {
-            outcome = 23;
-            direction = String::from("left");
-        }
-        // Move towards escape with best move
-        else if down_survival && can_escape_down && down_best
-        {
-            outcome = 24;
-            direction = String::from("down");
-        } else if up_survival && can_escape_up && up_best{
-            outcome = 25;
-            direction = String::from("up");
-        } else if right_survival
-            && can_escape_right
-            && right_best
-        {
-            outcome = 26;
-            direction = String::from("right");
-        } else if left_survival && can_escape_left && left_best
-        {
-            outcome = 27;
-            direction = String::from("left");
-        }
-        // Move towards escape with largest area of control
-        else if down_survival && can_escape_down && down_control == max_control {
-            outcome = 28;
-            direction = String::from("down");
-        } else if up_survival && can_escape_up && up_control == max_control {
-            outcome = 29;
-            direction = String::from("up");
-        } else if right_survival && can_escape_right && right_control == max_control {
-            outcome = 30;
-            direction = String::from("right");
-        } else if left_survival && can_escape_left && left_control == max_control {
-            outcome = 31;
-            direction = String::from("left");
-        }
-        // Move towards escape
-        else if down_survival && can_escape_down {
-            outcome = 32;
-            direction = String::from("down");
-        } else if up_survival && can_escape_up {
-            outcome = 33;
-            direction = String::from("up");
-        } else if right_survival && can_escape_right {
-            outcome = 34;
-            direction = String::from("right");
-        } else if left_survival && can_escape_left {
-            outcome = 35;
-            direction = String::from("left");
-        }
-        // Move towards best move, can escape
-        else if can_escape_down && down_best {
-            outcome = 36;
-            direction = String::from("down");
-        } else if can_escape_up && up_best {
-            outcome = 37;
-            direction = String::from("up");
-        } else if can_escape_right && right_best {
-            outcome = 38;
-            direction = String::from("right");
-        } else if can_escape_left && left_best {
-            outcome = 39;
-            direction = String::from("left");
-        }
-        // Move towards escape with largest area of control
-        else if can_escape_down && down_control == max_control {
-            outcome = 40;
-            direction = String::from("down");
-        } else if can_escape_up && up_control == max_control {
-            outcome = 41;
-            direction = String::from("up");
-        } else if can_escape_right && right_control == max_control {
-            outcome = 42;
-            direction = String::from("right");
-        } else if can_escape_left && left_control == max_control {
-            outcome = 43;
-            direction = String::from("left");
-        }
-        // Go for best move with most turns, no survival
-        else if down_best && down_area == max_area {
-            outcome = 44;
-            direction = String::from("down");
-        } else if up_best && up_area == max_area {
-            outcome = 45;
-            direction = String::from("up");
-        } else if right_best && right_area == max_area {
-            outcome = 46;
-            direction = String::from("right");
-        } else if left_best && left_area == max_area {
-            outcome = 47;
-            direction = String::from("left")
-        }
-        // Go for most turns, no survival
-        else if down_area == max_area {
-            outcome = 48;
-            direction = String::from("down");
-        } else if up_area == max_area {
-            outcome = 49;
-            direction = String::from("up");
-        } else if right_area == max_area {
-            outcome = 50;
-            direction = String::from("right");
-        } else if left_area == max_area {
-            outcome = 51;
-            direction = String::from("left")
-        }
-        // Default
-        else {
-            outcome = 52;
-            direction = String::from("up");
+        // Collect what's known about each direction
+        let mut features = Features {
+            will_kill,
+            ..Features::default()
+        };
+        for direction in Direction::ALL {
+            let pos = board.wrap(current_pos.get_neighbour(direction));
+            let result = results[direction.index()];
+
+            features.directions[direction.index()] = DirectionFeatures {
+                survival: result.is_alive(),
+                best: result == best_result,
+                can_escape: areas[direction.index()] >= max_search,
+                away_from_wall: !board.is_against_wall(pos) || board.get_food().contains(&pos),
+                towards_food: closest_food_exists
+                    && board.distance(pos, closest_food)
+                        < board.distance(current_pos, closest_food),
+                max_control: control_areas[direction.index()] == max_control,
+                max_area: areas[direction.index()] == max_area,
+            };
         }
 
+        // Take all data and decide on direction
+        let (direction, rule) = decide(&features);
+
         // Log decision
         self.log_data(format!(
             "
@@ -536,6 +265,10 @@ impl Game {
     decision: {}
    will kill: {}
    max turns: {}
+ search time: {}ms
+    hit rate: {:.1}% ({}/{})
+  last moves: {}
+      reused: {} hits, {} turns deep
  down result: {}
    up result: {}
 right result: {}
@@ -551,9 +284,20 @@ right result: {}
    left area: {}",
             board.get_turn(),
             direction,
-            outcome,
+            rule,
             will_kill,
             max_depth,
+            start.elapsed().as_millis(),
+            table.get_hit_rate(),
+            table.get_hits(),
+            table.get_lookups(),
+            last_moves
+                .iter()
+                .map(|direction| direction.map_or(String::from("-"), |d| d.to_string()))
+                .collect::<Vec<String>>()
+                .join(" "),
+            table.get_reused(),
+            reused_depth,
             down_board,
             up_board,
             right_board,
@@ -570,16 +314,198 @@ right result: {}
         ));
 
         // Return direction
+        (direction, rule)
+    }
+
+    // Returns the most visited direction of a Monte Carlo tree search
+    // The search runs until deadline, or MCTS_ITERATIONS times without one
+    fn calculate_mcts_move(
+        &self,
+        board: &Board,
+        start: Instant,
+        deadline: Option<Instant>,
+    ) -> Direction {
+        let visits = match deadline {
+            Some(deadline) => board.mcts(
+                &self.ruleset,
+                deadline.saturating_duration_since(Instant::now()),
+                u32::MAX,
+                board.zobrist_hash(),
+            ),
+            None => board.mcts(
+                &self.ruleset,
+                Duration::MAX,
+                MCTS_ITERATIONS,
+                board.zobrist_hash(),
+            ),
+        };
+        let direction = Direction::ALL
+            .into_iter()
+            .max_by_key(|direction| visits[direction.index()])
+            .unwrap();
+
+        self.log_data(format!(
+            "
+        turn: {}
+   direction: {}
+ search time: {}ms
+ down visits: {}
+   up visits: {}
+right visits: {}
+ left visits: {}",
+            board.get_turn(),
+            direction,
+            start.elapsed().as_millis(),
+            visits[Direction::Down.index()],
+            visits[Direction::Up.index()],
+            visits[Direction::Right.index()],
+            visits[Direction::Left.index()],
+        ));
+
         direction
     }
 
+    // Returns the time available for searching with profile, leaving room for network latency
+    pub fn time_budget(&self, profile: &Profile, latency: i32) -> Duration {
+        // The reported latency includes last turn's search, so only the excess is network
+        let search_time = self.timeout as f32 * profile.get_time_budget();
+        let network_latency = max(0, latency - search_time as i32);
+
+        Duration::from_millis(max(0, search_time as i32 - network_latency) as u64)
+    }
+
+    // Runs op on the search pool
+    fn run<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        match &self.thread_pool {
+            Some(thread_pool) => thread_pool.install(op),
+            None => op(),
+        }
+    }
+
+    // Returns the longest path I can travel in each direction, up to max_search, indexed by Direction
+    fn escape_areas(&self, board: &Board, max_search: i32, health: i32) -> [i32; DIRECTIONS + 1] {
+        let you = &board.get_snakes()[YOU_ID as usize];
+        let areas: Vec<i32> = Direction::ALL
+            .par_iter()
+            .map(|&direction| {
+                board.longest_path(
+                    &self.ruleset,
+                    board.wrap(you.get_head().get_neighbour(direction)),
+                    0,
+                    max_search,
+                    &mut vec![false; board.tile_count()],
+                    0,
+                    health,
+                )
+            })
+            .collect();
+
+        areas.try_into().unwrap()
+    }
+
+    // Searches all four directions to max_depth in parallel, indexed by Direction
+    pub fn search_directions(
+        &self,
+        board: &Board,
+        table: &TranspositionTable,
+        max_depth: i32,
+    ) -> [Evaluation; DIRECTIONS + 1] {
+        self.search_directions_until(board, table, max_depth, None)
+            .unwrap()
+    }
+
+    // Searches like search_directions, giving up once deadline passes
+    // Returns None if it did, a search cut off partway through can't be used
+    fn search_directions_until(
+        &self,
+        board: &Board,
+        table: &TranspositionTable,
+        max_depth: i32,
+        deadline: Option<Instant>,
+    ) -> Option<[Evaluation; DIRECTIONS + 1]> {
+        let profile = self.get_profile(board);
+        let mut board = board.clone();
+        board.set_deadline(deadline);
+        let results: Vec<Evaluation> = Direction::ALL
+            .par_iter()
+            .map(|&direction| match self.search_mode {
+                SearchMode::Paranoid => {
+                    board
+                        .clone()
+                        .check_paranoid(direction, &self.ruleset, profile, 0, max_depth)
+                }
+                SearchMode::Expected => board.check_move_expected(
+                    direction,
+                    &self.ruleset,
+                    profile,
+                    table,
+                    &self.opponent_model,
+                    0,
+                    max_depth,
+                ),
+                _ => board.check_move(direction, &self.ruleset, profile, table, 0, max_depth),
+            })
+            .collect();
+
+        if board.is_past_deadline() {
+            return None;
+        }
+        Some(results.try_into().unwrap())
+    }
+
+    // Searches one level deeper at a time up to max_depth, stopping at deadline
+    // Levels that won't fit before it aren't started, and a level still running then is discarded
+    // Every level is searched without a deadline
+    // Returns the results of the deepest completed search and its depth
+    pub fn iterative_deepening(
+        &self,
+        board: &Board,
+        table: &TranspositionTable,
+        deadline: Option<Instant>,
+    ) -> ([Evaluation; DIRECTIONS + 1], i32) {
+        // Every level multiplies the number of positions by the branching factor
+        // Alpha-beta only has to search about the square root of the tree
+        let num_snakes = board.get_snakes().len() as u32;
+        let branching = match self.search_mode {
+            SearchMode::Paranoid => DIRECTIONS.pow(num_snakes.div_ceil(2)),
+            _ => DIRECTIONS.pow(num_snakes),
+        } as u32;
+
+        // The first level is always finished, so there's a move to make
+        let mut depth = 1;
+        let level_start = Instant::now();
+        let mut results = self.search_directions(board, table, depth);
+        let mut last_search = level_start.elapsed();
+
+        while depth < self.max_depth {
+            // Estimate the next level from the time taken by the last one
+            if let Some(deadline) = deadline {
+                let estimate = last_search.saturating_mul(branching);
+                if Instant::now() + estimate > deadline {
+                    break;
+                }
+            }
+
+            let level_start = Instant::now();
+            match self.search_directions_until(board, table, depth + 1, deadline) {
+                Some(deeper) => results = deeper,
+                None => break,
+            }
+            last_search = level_start.elapsed();
+            depth += 1;
+        }
+
+        (results, depth)
+    }
+
     // Prints data to stdout and writes to log file
+    #[allow(clippy::absurd_extreme_comparisons)]
     pub fn log_data(&self, data: String) {
         let data = data + "\n\n";
         if LOG_LEVEL >= 1 {
             print!("{}", data);
         }
-        
+
         if LOG_LEVEL >= 2 {
             let mut file: File = OpenOptions::new()
                 .append(true)
@@ -594,9 +520,16 @@ right result: {}
 
 #[cfg(test)]
 mod tests {
-    use crate::constants::_TEST_PATH;
+    use crate::board::simulate::SearchMode;
+    use crate::config::{Config, Profile};
+    use crate::constants::{_TEST_PATH, TABLE_SIZE};
+    use crate::direction::Direction;
     use crate::load_object;
     use crate::move_request::MoveRequest;
+    use crate::transposition_table::TranspositionTable;
+    use rayon::ThreadPoolBuilder;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn test_decision() {
@@ -604,10 +537,28 @@ mod tests {
 
         let values = data.into_values();
         let board = values.2.into_board(values.3, values.1);
-        let game = values.0.into_game();
+        let mut game = values.0.into_game();
+        game.set_max_depth(4);
+        game.set_fixed_depth(true);
+        let decision = game.decide_move(board);
+
+        assert_eq!(decision, (Direction::Left, "control"));
+    }
+
+    #[test]
+    fn test_decision_thread_pool() {
+        let data = load_object!(MoveRequest, String::from("test_board-04"), _TEST_PATH);
+
+        let values = data.into_values();
+        let board = values.2.into_board(values.3, values.1);
+        let mut game = values.0.into_game();
+        game.set_max_depth(4);
+        game.set_fixed_depth(true);
+        let thread_pool = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+        game.set_thread_pool(Arc::new(thread_pool));
         let direction = game.calculate_move(board);
 
-        assert_eq!(direction, String::from("left"));
+        assert_eq!(direction, Direction::Left);
     }
 
     #[test]
@@ -616,9 +567,159 @@ mod tests {
 
         let values = data.into_values();
         let board = values.2.into_board(values.3, values.1);
-        let game = values.0.into_game();
+        let mut game = values.0.into_game();
+        game.set_max_depth(6);
+        game.set_fixed_depth(true);
+        let decision = game.decide_move(board);
+
+        assert_eq!(decision, (Direction::Down, "control_away_from_walls"));
+    }
+
+    // Food across the wrapped edge is closer going left, away from it on the board
+    #[test]
+    fn test_towards_food_wrapped() {
+        let data = load_object!(
+            MoveRequest,
+            String::from("towards_food_wrapped-01"),
+            _TEST_PATH
+        );
+
+        let values = data.into_values();
+        let board = values.2.into_board(values.3, values.1);
+        let mut game = values.0.into_game();
+        game.set_max_depth(3);
+        game.set_fixed_depth(true);
+        let decision = game.decide_move(board);
+
+        assert_eq!(decision, (Direction::Left, "food_away_from_walls"));
+    }
+
+    #[test]
+    fn test_avoid_headon_mcts() {
+        let data = load_object!(MoveRequest, String::from("test_board-06"), _TEST_PATH);
+
+        let values = data.into_values();
+        let board = values.2.into_board(values.3, values.1);
+        let mut game = values.0.into_game();
+        game.set_search_mode(SearchMode::Mcts);
+        game.set_fixed_depth(true);
         let direction = game.calculate_move(board);
 
-        assert_eq!(direction, String::from("down"));
+        assert_eq!(direction, Direction::Down);
+    }
+
+    // The last turn's search answers part of the next one
+    #[test]
+    fn test_search_reuse() {
+        let load = |name| {
+            let values = load_object!(MoveRequest, name, _TEST_PATH).into_values();
+            (
+                values.0.into_game(),
+                values.2.into_board(values.3, values.1),
+            )
+        };
+        let (game, turn1) = load("history-01-turn1");
+        let (_, turn2) = load("history-01-turn2");
+        let table = TranspositionTable::new(TABLE_SIZE);
+
+        // Positions only transpose within a turn, so the last search has to go deeper to help
+        game.search_directions(&turn1, &table, 4);
+        table.new_search();
+
+        assert_eq!(table.get_depth(turn2.zobrist_hash()), Some(3));
+        game.search_directions(&turn2, &table, 3);
+        assert!(table.get_reused() > 0);
+    }
+
+    // A game started by three snakes keeps the standard profile once one is eliminated
+    #[test]
+    fn test_get_profile() {
+        let config = Config::load(&format!("{}config-01.json", _TEST_PATH)).unwrap();
+        let data = load_object!(MoveRequest, String::from("history-01-turn3"), _TEST_PATH);
+
+        let values = data.into_values();
+        let board = values.2.into_board(values.3, values.1);
+        let mut game = values.0.into_game();
+        game.set_config(Arc::new(config.clone()));
+
+        assert_eq!(
+            game.get_profile(&board),
+            config.get_profile(&game.ruleset, 2)
+        );
+        game.set_starting_snakes(3);
+        assert_eq!(
+            game.get_profile(&board),
+            config.get_profile(&game.ruleset, 3)
+        );
+        assert_ne!(
+            game.get_profile(&board),
+            config.get_profile(&game.ruleset, 2)
+        );
+    }
+
+    #[test]
+    fn test_time_budget() {
+        let data = load_object!(MoveRequest, String::from("test_board-04"), _TEST_PATH);
+        let game = data.into_values().0.into_game();
+        let profile = Profile::default();
+
+        assert_eq!(game.time_budget(&profile, 0), Duration::from_millis(250));
+        assert_eq!(game.time_budget(&profile, 300), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_iterative_deepening() {
+        let data = load_object!(MoveRequest, String::from("test_board-04"), _TEST_PATH);
+
+        let values = data.into_values();
+        let board = values.2.into_board(values.3, values.1);
+        let game = values.0.into_game();
+        let table = TranspositionTable::new(TABLE_SIZE);
+        let (results, depth) = game.iterative_deepening(&board, &table, Some(Instant::now()));
+
+        assert_eq!(depth, 1);
+        assert_eq!(
+            results,
+            game.search_directions(&board, &TranspositionTable::new(0), 1)
+        );
+    }
+
+    // Without a deadline every level is searched
+    #[test]
+    fn test_iterative_deepening_fixed() {
+        let data = load_object!(MoveRequest, String::from("test_board-04"), _TEST_PATH);
+
+        let values = data.into_values();
+        let board = values.2.into_board(values.3, values.1);
+        let mut game = values.0.into_game();
+        game.set_max_depth(3);
+        let table = TranspositionTable::new(TABLE_SIZE);
+        let (results, depth) = game.iterative_deepening(&board, &table, None);
+
+        assert_eq!(depth, 3);
+        assert_eq!(
+            results,
+            game.search_directions(&board, &TranspositionTable::new(0), 3)
+        );
+    }
+
+    // A level still running at the deadline is thrown away, and none of it is stored
+    #[test]
+    fn test_search_directions_until() {
+        let data = load_object!(MoveRequest, String::from("test_board-04"), _TEST_PATH);
+
+        let values = data.into_values();
+        let board = values.2.into_board(values.3, values.1);
+        let game = values.0.into_game();
+        let table = TranspositionTable::new(TABLE_SIZE);
+
+        assert_eq!(
+            game.search_directions_until(&board, &table, 3, Some(Instant::now())),
+            None
+        );
+        assert_eq!(
+            game.search_directions(&board, &table, 3),
+            game.search_directions(&board, &TranspositionTable::new(0), 3)
+        );
     }
 }
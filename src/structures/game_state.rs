@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::board::Board;
+use crate::constants::{TABLE_SIZE, YOU_ID};
+use crate::direction::Direction;
+use crate::game::Game;
+use crate::opponent_model::{OpponentModel, Tendencies};
+use crate::transposition_table::TranspositionTable;
+
+// What is remembered about one game between requests
+#[derive(Debug, Default)]
+pub struct GameState {
+    // The board of the last move and the API id of each of its snakes, by board id
+    previous_board: Option<Board>,
+    previous_ids: Vec<String>,
+    previous_decision: Option<Direction>,
+    // Moves made by each opponent, by API id, oldest first
+    history: HashMap<String, Vec<Direction>>,
+    // What kinds of move each opponent made, by API id
+    tendencies: HashMap<String, Tendencies>,
+    // Results of every search this game, created by the first one
+    table: Option<Arc<TranspositionTable>>,
+    // Snakes on the first board seen, the number the game started with
+    starting_snakes: Option<usize>,
+}
+
+impl GameState {
+    pub fn new() -> GameState {
+        GameState::default()
+    }
+
+    pub fn get_previous_board(&self) -> Option<&Board> {
+        self.previous_board.as_ref()
+    }
+
+    pub fn get_starting_snakes(&self) -> Option<usize> {
+        self.starting_snakes
+    }
+
+    pub fn get_previous_decision(&self) -> Option<Direction> {
+        self.previous_decision
+    }
+
+    // Returns the moves made by the opponent with API id, oldest first
+    pub fn get_history(&self, id: &str) -> &[Direction] {
+        self.history.get(id).map_or(&[], |moves| moves.as_slice())
+    }
+
+    // Returns the tendencies of the opponent with API id
+    pub fn get_tendencies(&self, id: &str) -> Tendencies {
+        self.tendencies.get(id).copied().unwrap_or_default()
+    }
+
+    // Returns the tendencies of the snakes with API ids, indexed by board id like ids
+    pub fn get_opponent_model(&self, ids: &[String]) -> OpponentModel {
+        OpponentModel::new(ids.iter().map(|id| self.get_tendencies(id)).collect())
+    }
+
+    // Returns the search kept for this game, so the next turn can reuse it
+    pub fn get_table(&mut self) -> Arc<TranspositionTable> {
+        Arc::clone(
+            self.table
+                .get_or_insert_with(|| Arc::new(TranspositionTable::new(TABLE_SIZE))),
+        )
+    }
+
+    // Records the move every opponent made since the previous board
+    pub fn observe(&mut self, board: &Board) {
+        let previous_board = match &self.previous_board {
+            Some(previous_board) => previous_board,
+            None => return,
+        };
+
+        let moves = board.find_joint_move(previous_board);
+        for (index, direction) in moves.into_iter().enumerate() {
+            let snake = previous_board.get_snake(index as u8);
+            if let (Some(direction), Some(snake)) = (direction, snake) {
+                if snake.get_id() == YOU_ID {
+                    continue;
+                }
+                let id = &self.previous_ids[index];
+                self.tendencies.entry(id.clone()).or_default().observe(
+                    previous_board,
+                    snake,
+                    direction,
+                );
+                self.history.entry(id.clone()).or_default().push(direction);
+            }
+        }
+    }
+
+    // Returns the move game makes on board, using and updating what's remembered about the game
+    // ids are the API ids of the snakes on board, by board id
+    pub fn calculate_move(
+        &mut self,
+        game: &mut Game,
+        mut board: Board,
+        ids: Vec<String>,
+    ) -> Direction {
+        // Moves across the edges of a wrapped board are only found on a board that wraps
+        board.set_wrapped(game.get_ruleset().is_wrapped());
+
+        // Remember what the opponents did since the last move
+        self.observe(&board);
+
+        // The profile depends on how many snakes started, not how many are left
+        let starting_snakes = *self
+            .starting_snakes
+            .get_or_insert_with(|| board.get_max_snakes());
+        game.set_starting_snakes(starting_snakes);
+
+        // Warm start the search from the last turn's
+        game.set_table(self.get_table());
+        if let Some(previous_board) = &self.previous_board {
+            game.set_previous_board(previous_board.clone());
+        }
+        game.set_opponent_model(self.get_opponent_model(&ids));
+
+        let direction = game.calculate_move(board.clone());
+        self.record_decision(board, ids, direction);
+        direction
+    }
+
+    // Remembers the board and the move I made on it
+    pub fn record_decision(&mut self, board: Board, ids: Vec<String>, direction: Direction) {
+        self.previous_board = Some(board);
+        self.previous_ids = ids;
+        self.previous_decision = Some(direction);
+    }
+}
+
+// The state of every game in progress, by game id
+#[derive(Debug, Default)]
+pub struct GameRegistry {
+    games: Mutex<HashMap<String, Arc<Mutex<GameState>>>>,
+}
+
+impl GameRegistry {
+    pub fn new() -> GameRegistry {
+        GameRegistry::default()
+    }
+
+    // Starts remembering the game with id
+    pub fn start(&self, id: &str) {
+        let mut games = self.games.lock().unwrap();
+        games.insert(id.to_string(), Arc::new(Mutex::new(GameState::new())));
+    }
+
+    // Returns the state of the game with id
+    // Games missing a start, like after a restart, begin with nothing remembered
+    pub fn get(&self, id: &str) -> Arc<Mutex<GameState>> {
+        let mut games = self.games.lock().unwrap();
+        Arc::clone(games.entry(id.to_string()).or_default())
+    }
+
+    // Forgets the game with id
+    pub fn end(&self, id: &str) -> Option<Arc<Mutex<GameState>>> {
+        self.games.lock().unwrap().remove(id)
+    }
+
+    // Returns the number of games in progress
+    pub fn len(&self) -> usize {
+        self.games.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::_TEST_PATH;
+    use crate::load_object;
+    use crate::move_request::MoveRequest;
+
+    // Returns the board of a move request and the API ids of its snakes
+    fn load_turn(name: &str) -> (Board, Vec<String>) {
+        let (_, turn, board, you) = load_object!(MoveRequest, name, _TEST_PATH).into_values();
+        let ids = board.get_snake_ids(&you);
+        (board.into_board(you, turn), ids)
+    }
+
+    // observe()
+    #[test]
+    fn test_observe() {
+        let mut state = GameState::new();
+
+        for name in ["history-01-turn1", "history-01-turn2", "history-01-turn3"] {
+            let (board, ids) = load_turn(name);
+            state.observe(&board);
+            state.record_decision(board, ids, Direction::Up);
+        }
+
+        assert_eq!(state.get_history("a"), [Direction::Left, Direction::Down]);
+        // b was eliminated on the last turn
+        assert_eq!(state.get_history("b"), [Direction::Down]);
+        assert_eq!(state.get_history("you"), []);
+        assert_eq!(state.get_previous_decision(), Some(Direction::Up));
+        assert_eq!(state.get_previous_board().unwrap().get_turn(), 3);
+
+        // a moved left then down, towards b's head both times
+        let tendencies = state.get_tendencies("a");
+        assert_eq!(tendencies.get_moves(), 2);
+        assert_eq!(tendencies.get_aggression(), 0.75);
+        let (_, ids) = load_turn("history-01-turn3");
+        assert_eq!(state.get_opponent_model(&ids).get_tendencies(1), tendencies);
+    }
+
+    #[test]
+    fn test_observe_first_turn() {
+        let mut state = GameState::new();
+        let (board, _) = load_turn("history-01-turn2");
+
+        state.observe(&board);
+
+        assert_eq!(state.get_history("a"), []);
+    }
+
+    // calculate_move()
+    // The snakes of the first turn are kept after one is eliminated
+    #[test]
+    fn test_calculate_move_starting_snakes() {
+        let mut state = GameState::new();
+
+        for name in ["history-01-turn1", "history-01-turn2", "history-01-turn3"] {
+            let request = load_object!(MoveRequest, name, _TEST_PATH);
+            let mut game = request.get_game().clone().into_game();
+            game.set_max_depth(1);
+            game.set_fixed_depth(true);
+            let (board, ids) = load_turn(name);
+            state.calculate_move(&mut game, board, ids);
+        }
+
+        assert_eq!(state.get_starting_snakes(), Some(3));
+    }
+
+    #[test]
+    fn test_get_table() {
+        let mut state = GameState::new();
+
+        assert!(Arc::ptr_eq(&state.get_table(), &state.get_table()));
+    }
+
+    // GameRegistry
+    #[test]
+    fn test_registry() {
+        let registry = GameRegistry::new();
+
+        registry.start("one");
+        registry.get("two").lock().unwrap().record_decision(
+            load_turn("history-01-turn1").0,
+            Vec::new(),
+            Direction::Left,
+        );
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.get("two").lock().unwrap().get_previous_decision(),
+            Some(Direction::Left)
+        );
+        assert!(registry.end("one").is_some());
+        assert!(registry.end("one").is_none());
+        assert_eq!(registry.len(), 1);
+    }
+}
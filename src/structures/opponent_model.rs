@@ -0,0 +1,155 @@
+use crate::battlesnake::Battlesnake;
+use crate::board::Board;
+use crate::constants::DIRECTIONS;
+use crate::direction::Direction;
+
+// What moving a snake in a direction does: seeks food, hugs a wall, approaches another snake
+fn traits(board: &Board, snake: &Battlesnake, direction: Direction) -> [bool; 3] {
+    let head = snake.get_head();
+    let pos = board.wrap(head.get_neighbour(direction));
+    let closer = |target| board.distance(pos, target) < board.distance(head, target);
+
+    let food_seeking = board.find_closest_food(head).is_some_and(closer);
+    let wall_hugging = board.is_against_wall(pos);
+    let aggression = board
+        .get_snakes()
+        .iter()
+        .filter(|other| other.get_id() != snake.get_id())
+        .map(|other| other.get_head())
+        .min_by_key(|&other| board.distance(head, other))
+        .is_some_and(closer);
+
+    [food_seeking, wall_hugging, aggression]
+}
+
+// How often one opponent was seen doing each kind of move
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Tendencies {
+    moves: u32,
+    food_seeking: u32,
+    wall_hugging: u32,
+    aggression: u32,
+}
+
+impl Tendencies {
+    pub fn get_moves(&self) -> u32 {
+        self.moves
+    }
+
+    // Rates are smoothed towards 0.5, no preference, until there are moves to go by
+    fn rate(&self, count: u32) -> f64 {
+        (count as f64 + 1.0) / (self.moves as f64 + 2.0)
+    }
+
+    pub fn get_food_seeking(&self) -> f64 {
+        self.rate(self.food_seeking)
+    }
+
+    pub fn get_wall_hugging(&self) -> f64 {
+        self.rate(self.wall_hugging)
+    }
+
+    pub fn get_aggression(&self) -> f64 {
+        self.rate(self.aggression)
+    }
+
+    // Records snake moving in direction on board, the board before the move
+    pub fn observe(&mut self, board: &Board, snake: &Battlesnake, direction: Direction) {
+        let [food_seeking, wall_hugging, aggression] = traits(board, snake, direction);
+        self.moves += 1;
+        self.food_seeking += food_seeking as u32;
+        self.wall_hugging += wall_hugging as u32;
+        self.aggression += aggression as u32;
+    }
+
+    // Returns how likely snake is to move in each of its options on board, indexed like get_options
+    // Each option is weighted by the rate of every trait it has, and one minus the rate of the others
+    pub fn reply_probabilities(&self, board: &Board, snake: &Battlesnake) -> [f64; DIRECTIONS] {
+        let rates = [
+            self.get_food_seeking(),
+            self.get_wall_hugging(),
+            self.get_aggression(),
+        ];
+        let weights = snake.get_options().map(|direction| {
+            traits(board, snake, direction)
+                .iter()
+                .zip(rates)
+                .map(|(&has, rate)| if has { rate } else { 1.0 - rate })
+                .product::<f64>()
+        });
+
+        let total: f64 = weights.iter().sum();
+        weights.map(|weight| weight / total)
+    }
+}
+
+// The tendencies of every snake on a board, indexed by id
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OpponentModel {
+    tendencies: Vec<Tendencies>,
+}
+
+impl OpponentModel {
+    pub fn new(tendencies: Vec<Tendencies>) -> OpponentModel {
+        OpponentModel { tendencies }
+    }
+
+    // Snakes that haven't been seen have no preference
+    pub fn get_tendencies(&self, id: u8) -> Tendencies {
+        self.tendencies
+            .get(id as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    // Returns how likely snake is to move in each of its options on board, indexed like get_options
+    pub fn reply_probabilities(&self, board: &Board, snake: &Battlesnake) -> [f64; DIRECTIONS] {
+        self.get_tendencies(snake.get_id())
+            .reply_probabilities(board, snake)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::_TEST_PATH;
+    use crate::load_object;
+
+    // reply_probabilities()
+    #[test]
+    fn test_no_preference() {
+        let board = load_object!(Board, "test_board-04", _TEST_PATH);
+
+        for snake in board.get_snakes() {
+            let probabilities = OpponentModel::default().reply_probabilities(&board, snake);
+            assert_eq!(probabilities, [1.0 / 3.0; DIRECTIONS]);
+        }
+    }
+
+    #[test]
+    fn test_food_seeking() {
+        let board = load_object!(Board, "test_board-04", _TEST_PATH);
+        let snake = &board.get_snakes()[1];
+        let food = board.find_closest_food(snake.get_head()).unwrap();
+        let towards_food = |direction: &Direction| {
+            let pos = board.wrap(snake.get_head().get_neighbour(*direction));
+            board.distance(pos, food) < board.distance(snake.get_head(), food)
+        };
+
+        // Always seen going for food
+        let mut tendencies = Tendencies::default();
+        let direction = snake.get_options().into_iter().find(towards_food).unwrap();
+        for _ in 0..20 {
+            tendencies.observe(&board, snake, direction);
+        }
+        let probabilities = tendencies.reply_probabilities(&board, snake);
+
+        assert!(tendencies.get_food_seeking() > 0.9);
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        for (i, option) in snake.get_options().iter().enumerate() {
+            if !towards_food(option) {
+                assert!(probabilities[i] < 0.1);
+            }
+        }
+    }
+}
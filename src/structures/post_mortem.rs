@@ -0,0 +1,131 @@
+use crate::constants::TABLE_SIZE;
+use crate::direction::Direction;
+use crate::game::Game;
+use crate::game_export::GameExport;
+use crate::game_state::GameState;
+use crate::transposition_table::TranspositionTable;
+
+// How one turn of an exported game looks to this build
+#[derive(Clone, Debug, PartialEq)]
+pub struct TurnReview {
+    turn: i32,
+    // The move made in the game, None on the last frame
+    played: Option<Direction>,
+    // The move calculate_move picks now
+    chosen: Direction,
+    // Every move a search to the review depth survives
+    surviving: Vec<Direction>,
+}
+
+impl TurnReview {
+    pub fn get_turn(&self) -> i32 {
+        self.turn
+    }
+
+    pub fn get_played(&self) -> Option<Direction> {
+        self.played
+    }
+
+    pub fn get_chosen(&self) -> Direction {
+        self.chosen
+    }
+
+    pub fn get_surviving(&self) -> &Vec<Direction> {
+        &self.surviving
+    }
+
+    // Returns true if the move played dies while another move survives
+    pub fn is_losing_move(&self) -> bool {
+        match self.played {
+            Some(played) => !self.surviving.is_empty() && !self.surviving.contains(&played),
+            None => false,
+        }
+    }
+}
+
+// Steps through every frame of export the snake with id was alive in, deciding each move again
+// The game is remembered from frame to frame like the server does, so the moves match a live game
+// Survival is checked with a search of every direction to max_depth
+pub fn review_game(
+    export: &GameExport,
+    game: &mut Game,
+    id: &str,
+    max_depth: i32,
+) -> Vec<TurnReview> {
+    let mut state = GameState::new();
+    let mut reviews = Vec::new();
+
+    for frame in 0..export.get_frames().len() {
+        let (board, ids) = match export.get_board(frame, id) {
+            Some(seen) => seen,
+            None => break,
+        };
+
+        let chosen = state.calculate_move(game, board.clone(), ids);
+        let table = TranspositionTable::new(TABLE_SIZE);
+        let results = game.search_directions(&board, &table, max_depth);
+        let surviving = Direction::ALL
+            .into_iter()
+            .filter(|direction| results[direction.index()].is_alive())
+            .collect();
+
+        reviews.push(TurnReview {
+            turn: board.get_turn(),
+            played: export.get_move(frame, id),
+            chosen,
+            surviving,
+        });
+    }
+
+    reviews
+}
+
+// Returns the last turn where the move played died while another move survived
+// After it the game was lost whatever was played
+pub fn find_divergence(reviews: &[TurnReview]) -> Option<&TurnReview> {
+    reviews.iter().rev().find(|review| review.is_losing_move())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::_TEST_PATH;
+
+    #[test]
+    fn test_review_game() {
+        let export = GameExport::load(&format!("{}game_export-01.json", _TEST_PATH)).unwrap();
+        let mut game = export.create_game();
+        game.set_max_depth(3);
+
+        let reviews = review_game(&export, &mut game, "gs_me", 3);
+
+        // The last frame is after my elimination
+        assert_eq!(reviews.len(), 2);
+        assert_eq!(reviews[1].get_played(), Some(Direction::Left));
+        assert_eq!(
+            reviews[1].get_surviving(),
+            &[Direction::Down, Direction::Up]
+        );
+        assert!(reviews[1]
+            .get_surviving()
+            .contains(&reviews[1].get_chosen()));
+        assert!(!reviews[0].is_losing_move());
+
+        let divergence = find_divergence(&reviews).unwrap();
+        assert_eq!(divergence.get_turn(), 1);
+    }
+
+    #[test]
+    fn test_find_divergence_none() {
+        let review = TurnReview {
+            turn: 4,
+            played: Some(Direction::Up),
+            chosen: Direction::Up,
+            surviving: Vec::new(),
+        };
+
+        // Nothing survived, so the game was already lost
+        assert!(!review.is_losing_move());
+        assert_eq!(find_divergence(&[review]), None);
+    }
+}
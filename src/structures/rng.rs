@@ -0,0 +1,53 @@
+// Small seeded random number generator (SplitMix64), so simulations can be replayed
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    // Returns the next random number in the sequence
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        Rng::mix(self.state)
+    }
+
+    // Scrambles z, so inputs that differ in a few bits give unrelated outputs
+    pub fn mix(mut z: u64) -> u64 {
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Returns a random number in 0..bound
+    pub fn gen_range(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed() {
+        let mut rng_1 = Rng::new(42);
+        let mut rng_2 = Rng::new(42);
+
+        for _ in 0..10 {
+            assert_eq!(rng_1.next_u64(), rng_2.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_gen_range() {
+        let mut rng = Rng::new(7);
+
+        for _ in 0..100 {
+            assert!(rng.gen_range(11) < 11);
+        }
+    }
+}
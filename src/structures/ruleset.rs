@@ -15,6 +15,7 @@ pub struct Ruleset {
 }
 
 impl Ruleset {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         name: String,
         version: String,
@@ -49,7 +50,51 @@ impl Ruleset {
         &self.version
     }
 
+    pub fn get_food_spawn_chance(&self) -> i32 {
+        self.food_spawn_chance
+    }
+
     pub fn get_minimum_food(&self) -> i32 {
         self.minimum_food
     }
+
+    pub fn get_hazard_damage_per_turn(&self) -> i32 {
+        self.hazard_damage_per_turn
+    }
+
+    pub fn get_shrink_every_n_turns(&self) -> i32 {
+        self.shrink_every_n_turns
+    }
+
+    pub fn get_allow_body_collisions(&self) -> bool {
+        self.allow_body_collisions
+    }
+
+    pub fn get_shared_elimination(&self) -> bool {
+        self.shared_elimination
+    }
+
+    pub fn get_shared_health(&self) -> bool {
+        self.shared_health
+    }
+
+    pub fn get_shared_length(&self) -> bool {
+        self.shared_length
+    }
+
+    pub fn is_constrictor(&self) -> bool {
+        self.name == "constrictor"
+    }
+
+    pub fn is_royale(&self) -> bool {
+        self.name == "royale"
+    }
+
+    pub fn is_squad(&self) -> bool {
+        self.name == "squad"
+    }
+
+    pub fn is_wrapped(&self) -> bool {
+        self.name == "wrapped"
+    }
 }
@@ -0,0 +1,472 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufReader, ErrorKind};
+use std::sync::Arc;
+
+use crate::arena::{Arena, Player};
+use crate::config::Config;
+use crate::constants::MAX_DEPTH;
+use crate::ruleset::Ruleset;
+
+// 95% of a normal distribution lies within this many standard deviations
+const CONFIDENCE: f64 = 1.96;
+
+fn default_player() -> String {
+    String::from("curunir")
+}
+
+fn default_max_depth() -> i32 {
+    MAX_DEPTH
+}
+
+// One engine in a tournament, as written in the tournament file
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EngineSettings {
+    name: String,
+    // Any arena player: curunir, paranoid, mcts, expected, random or hungry
+    #[serde(default = "default_player")]
+    player: String,
+    #[serde(default = "default_max_depth")]
+    max_depth: i32,
+    // Searches to max_depth without a time limit, so the engine's games can be reproduced
+    #[serde(default)]
+    fixed_depth: bool,
+    // Config file with the engine's evaluation weights, the config in use without one
+    #[serde(default)]
+    config: Option<String>,
+}
+
+impl EngineSettings {
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    // Returns the arena player set up like self
+    pub fn load_player(&self) -> io::Result<Player> {
+        let invalid = |message| io::Error::new(ErrorKind::InvalidInput, message);
+        let mut player = Player::from_name(&self.player)
+            .ok_or_else(|| invalid(format!("unknown player {}", self.player)))?;
+
+        if let Player::Curunir(engine) = &mut player {
+            engine.set_max_depth(self.max_depth);
+            engine.set_fixed_depth(self.fixed_depth);
+            if let Some(path) = &self.config {
+                engine.set_config(Arc::new(Config::load(path)?));
+            }
+        } else if self.config.is_some() {
+            return Err(invalid(format!("{} is a bot without a config", self.name)));
+        }
+
+        Ok(player)
+    }
+}
+
+// Seeded games between engines, read from a JSON file
+// Missing settings keep their defaults
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Tournament {
+    engines: Vec<EngineSettings>,
+    games: usize,
+    // Snakes in each game, engines take turns filling the seats
+    snakes: usize,
+    // Game i is played with seed + i
+    seed: u64,
+    size: i32,
+    timeout: i32,
+    max_turns: i32,
+    ruleset: String,
+}
+
+impl Default for Tournament {
+    fn default() -> Tournament {
+        Tournament {
+            engines: Vec::new(),
+            games: 100,
+            snakes: 2,
+            seed: 0,
+            size: 11,
+            timeout: 100,
+            max_turns: 1000,
+            ruleset: String::from("standard"),
+        }
+    }
+}
+
+impl Tournament {
+    // Reads a tournament from the JSON file at path
+    pub fn load(path: &str) -> io::Result<Tournament> {
+        let reader = BufReader::new(File::open(path)?);
+        let tournament: Tournament = serde_json::from_reader(reader)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+
+        if tournament.engines.len() < 2 || tournament.snakes < 2 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "a tournament needs at least two engines and two snakes a game",
+            ));
+        }
+        if tournament.snakes > Arena::max_snakes() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("games can't have more than {} snakes", Arena::max_snakes()),
+            ));
+        }
+        Ok(tournament)
+    }
+
+    pub fn get_engines(&self) -> &Vec<EngineSettings> {
+        &self.engines
+    }
+
+    pub fn get_games(&self) -> usize {
+        self.games
+    }
+
+    // Returns the engine in each seat of game, indexed like engines
+    // Engines move one seat along every game, so each plays every seat and opponent
+    pub fn seats(&self, game: usize) -> Vec<usize> {
+        (0..self.snakes)
+            .map(|seat| (game + seat) % self.engines.len())
+            .collect()
+    }
+
+    // Plays every game, calling on_game with each result as it finishes
+    pub fn run(&self, mut on_game: impl FnMut(&GameRecord)) -> io::Result<Vec<GameRecord>> {
+        let players = self
+            .engines
+            .iter()
+            .map(EngineSettings::load_player)
+            .collect::<io::Result<Vec<Player>>>()?;
+        // The standard settings, with hazards for royale games
+        let ruleset = Ruleset::new(
+            self.ruleset.clone(),
+            String::from("tournament"),
+            15,
+            1,
+            14,
+            25,
+            false,
+            false,
+            false,
+            false,
+        );
+
+        let mut records = Vec::with_capacity(self.games);
+        for game in 0..self.games {
+            let seats = self.seats(game);
+            let seed = self.seed.wrapping_add(game as u64);
+            let arena = Arena::new(
+                ruleset.clone(),
+                self.size,
+                self.size,
+                self.timeout,
+                self.max_turns,
+                seed,
+            );
+            let seated: Vec<Player> = seats.iter().map(|&i| players[i].clone()).collect();
+            let result = arena.play(&seated);
+
+            // Every turn a seat moved in, the winner outlasts the snakes eliminated on the last turn
+            let mut survived = vec![0; seats.len()];
+            for moves in result.get_moves() {
+                for &(seat, _) in moves {
+                    survived[seat] += 1;
+                }
+            }
+            if let Some(winner) = result.get_winner() {
+                survived[winner] += 1;
+            }
+
+            let record = GameRecord {
+                game,
+                seed,
+                engines: seats
+                    .iter()
+                    .map(|&i| self.engines[i].name.clone())
+                    .collect(),
+                winner: result
+                    .get_winner()
+                    .map(|seat| self.engines[seats[seat]].name.clone()),
+                turns: result.get_turns(),
+                survived,
+            };
+            on_game(&record);
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+}
+
+// How one tournament game went, written one per line to the results file
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct GameRecord {
+    game: usize,
+    seed: u64,
+    // The engine in each seat
+    engines: Vec<String>,
+    winner: Option<String>,
+    turns: i32,
+    // How long each seat lasted, snakes that lasted longer placed higher
+    survived: Vec<i32>,
+}
+
+impl GameRecord {
+    pub fn get_game(&self) -> usize {
+        self.game
+    }
+
+    pub fn get_engines(&self) -> &Vec<String> {
+        &self.engines
+    }
+
+    pub fn get_winner(&self) -> Option<&String> {
+        self.winner.as_ref()
+    }
+
+    pub fn get_turns(&self) -> i32 {
+        self.turns
+    }
+}
+
+// Returns the Elo difference that makes score the expected score, infinite for 0 and 1
+fn elo(score: f64) -> f64 {
+    400.0 * (score / (1.0 - score)).log10()
+}
+
+// One engine's results against the other engines, every pair of snakes in a game counts as a match
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Standing {
+    name: String,
+    wins: u32,
+    draws: u32,
+    losses: u32,
+}
+
+impl Standing {
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_wins(&self) -> u32 {
+        self.wins
+    }
+
+    pub fn get_draws(&self) -> u32 {
+        self.draws
+    }
+
+    pub fn get_losses(&self) -> u32 {
+        self.losses
+    }
+
+    pub fn get_matches(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    // Returns the share of points won, draws are worth half
+    pub fn get_score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.get_matches().max(1) as f64
+    }
+
+    // Returns the Elo rating relative to the engines played against
+    pub fn get_elo(&self) -> f64 {
+        elo(self.get_score())
+    }
+
+    // Returns the half width of the 95% confidence interval of get_elo
+    // Infinite when the score or either end of the interval is 0 or 1, or before any matches are played
+    pub fn get_elo_margin(&self) -> f64 {
+        if self.get_matches() == 0 {
+            return f64::INFINITY;
+        }
+
+        let matches = self.get_matches() as f64;
+        let score = self.get_score();
+        let variance = (self.wins as f64 * (1.0 - score).powi(2)
+            + self.draws as f64 * (0.5 - score).powi(2)
+            + self.losses as f64 * score.powi(2))
+            / matches;
+        let error = CONFIDENCE * (variance / matches).sqrt();
+
+        // Elo is infinite at a score of 0 or 1, so the interval has no width to halve
+        let (low, high) = (score - error, score + error);
+        if low <= 0.0 || high >= 1.0 {
+            return f64::INFINITY;
+        }
+        (elo(high) - elo(low)) / 2.0
+    }
+}
+
+// Returns the standing of every engine in names, in the same order, from records
+pub fn standings(names: &[String], records: &[GameRecord]) -> Vec<Standing> {
+    let mut standings: Vec<Standing> = names
+        .iter()
+        .map(|name| Standing {
+            name: name.clone(),
+            ..Standing::default()
+        })
+        .collect();
+    let index = |name: &String| names.iter().position(|other| other == name);
+
+    for record in records {
+        for (a, name) in record.engines.iter().enumerate() {
+            for (b, other) in record.engines.iter().enumerate() {
+                // Games an engine plays against itself don't rate it
+                let standing = match index(name) {
+                    Some(i) if name != other => &mut standings[i],
+                    _ => continue,
+                };
+                match record.survived[a].cmp(&record.survived[b]) {
+                    std::cmp::Ordering::Greater => standing.wins += 1,
+                    std::cmp::Ordering::Equal => standing.draws += 1,
+                    std::cmp::Ordering::Less => standing.losses += 1,
+                }
+            }
+        }
+    }
+
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::_TEST_PATH;
+
+    fn record(engines: [&str; 2], survived: [i32; 2]) -> GameRecord {
+        GameRecord {
+            game: 0,
+            seed: 0,
+            engines: engines.iter().map(|name| name.to_string()).collect(),
+            winner: None,
+            turns: survived[0].max(survived[1]),
+            survived: survived.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_load() {
+        let tournament = Tournament::load(&format!("{}tournament-01.json", _TEST_PATH)).unwrap();
+
+        assert_eq!(tournament.engines.len(), 3);
+        assert_eq!(tournament.engines[2].max_depth, 3);
+        assert!(tournament.engines[2].fixed_depth);
+        // Settings missing from the file keep their defaults
+        assert_eq!(tournament.size, 11);
+        assert_eq!(tournament.engines[0].player, "curunir");
+        assert_eq!(tournament.engines[0].max_depth, MAX_DEPTH);
+        assert!(!tournament.engines[0].fixed_depth);
+    }
+
+    #[test]
+    fn test_seats() {
+        let tournament = Tournament::load(&format!("{}tournament-01.json", _TEST_PATH)).unwrap();
+
+        assert_eq!(tournament.seats(0), [0, 1]);
+        assert_eq!(tournament.seats(2), [2, 0]);
+        assert_eq!(tournament.seats(4), [1, 2]);
+    }
+
+    #[test]
+    fn test_run_bots() {
+        let tournament = Tournament {
+            engines: vec![
+                EngineSettings {
+                    name: String::from("hungry"),
+                    player: String::from("hungry"),
+                    max_depth: MAX_DEPTH,
+                    fixed_depth: false,
+                    config: None,
+                },
+                EngineSettings {
+                    name: String::from("random"),
+                    player: String::from("random"),
+                    max_depth: MAX_DEPTH,
+                    fixed_depth: false,
+                    config: None,
+                },
+            ],
+            games: 6,
+            size: 7,
+            ..Tournament::default()
+        };
+
+        let mut played = 0;
+        let records = tournament.run(|_| played += 1).unwrap();
+        let names = ["hungry", "random"].map(String::from);
+        let standings = standings(&names, &records);
+
+        assert_eq!(played, 6);
+        assert_eq!(records, tournament.run(|_| ()).unwrap());
+        assert_eq!(standings[0].get_matches(), 6);
+        assert_eq!(standings[0].get_wins(), standings[1].get_losses());
+    }
+
+    #[test]
+    fn test_standings() {
+        let names = ["a", "b"].map(String::from);
+        let records = [
+            record(["a", "b"], [10, 5]),
+            record(["b", "a"], [7, 7]),
+            record(["b", "a"], [3, 9]),
+            record(["a", "a"], [3, 9]),
+        ];
+
+        let standings = standings(&names, &records);
+
+        assert_eq!(
+            (standings[0].wins, standings[0].draws, standings[0].losses),
+            (2, 1, 0)
+        );
+        assert!((standings[0].get_score() - 5.0 / 6.0).abs() < 1e-9);
+        assert!(standings[0].get_elo() > 0.0);
+        assert!((standings[0].get_elo() + standings[1].get_elo()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_elo() {
+        let even = Standing {
+            wins: 40,
+            draws: 20,
+            losses: 40,
+            ..Standing::default()
+        };
+        let more = Standing {
+            wins: 400,
+            draws: 200,
+            losses: 400,
+            ..Standing::default()
+        };
+
+        assert_eq!(even.get_elo(), 0.0);
+        assert!((elo(0.75) - 190.85).abs() < 0.01);
+        // More matches narrow the interval
+        assert!(more.get_elo_margin() < even.get_elo_margin());
+        assert!(even.get_elo_margin() > 0.0);
+    }
+
+    // Engines that never finished a match have no interval to narrow
+    #[test]
+    fn test_elo_margin_no_matches() {
+        let standing = Standing::default();
+
+        assert_eq!(standing.get_elo_margin(), f64::INFINITY);
+    }
+
+    // Sweeps and intervals that reach a sweep have no upper or lower bound
+    #[test]
+    fn test_elo_margin_sweep() {
+        let standing = |wins, losses| Standing {
+            wins,
+            losses,
+            ..Standing::default()
+        };
+
+        assert_eq!(standing(6, 0).get_elo_margin(), f64::INFINITY);
+        assert_eq!(standing(0, 6).get_elo_margin(), f64::INFINITY);
+        assert_eq!(standing(5, 1).get_elo_margin(), f64::INFINITY);
+        assert!(standing(50, 10).get_elo_margin().is_finite());
+    }
+}
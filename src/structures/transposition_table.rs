@@ -0,0 +1,186 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::evaluation::Evaluation;
+
+// A search result stored for one position
+struct Entry {
+    hash: u64,
+    depth: i32,
+    // The search that stored the result
+    generation: u32,
+    result: Vec<Evaluation>,
+}
+
+// Fixed size table of search results, shared between the search threads
+// Positions are stored in the slot hash % size, replacing shallower results
+// A table kept between turns warm starts the next search, results stay valid since positions don't change
+pub struct TranspositionTable {
+    slots: Vec<Mutex<Option<Entry>>>,
+    generation: AtomicU32,
+    lookups: AtomicU64,
+    hits: AtomicU64,
+    // Hits on results stored by an earlier search
+    reused: AtomicU64,
+}
+
+impl TranspositionTable {
+    // A size of 0 disables the table
+    pub fn new(size: usize) -> TranspositionTable {
+        TranspositionTable {
+            slots: (0..size).map(|_| Mutex::new(None)).collect(),
+            generation: AtomicU32::new(0),
+            lookups: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            reused: AtomicU64::new(0),
+        }
+    }
+
+    // Starts the next search, results stored until now count as reused
+    // The statistics are reset, so they only cover the new search
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.lookups.store(0, Ordering::Relaxed);
+        self.hits.store(0, Ordering::Relaxed);
+        self.reused.store(0, Ordering::Relaxed);
+    }
+
+    pub fn get_lookups(&self) -> u64 {
+        self.lookups.load(Ordering::Relaxed)
+    }
+
+    pub fn get_hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn get_reused(&self) -> u64 {
+        self.reused.load(Ordering::Relaxed)
+    }
+
+    // Returns the percentage of lookups that found a result
+    pub fn get_hit_rate(&self) -> f32 {
+        match self.get_lookups() {
+            0 => 0.0,
+            lookups => self.get_hits() as f32 * 100.0 / lookups as f32,
+        }
+    }
+
+    fn slot(&self, hash: u64) -> Option<&Mutex<Option<Entry>>> {
+        if self.slots.is_empty() {
+            None
+        } else {
+            Some(&self.slots[(hash % self.slots.len() as u64) as usize])
+        }
+    }
+
+    // Returns the stored result for hash if it was searched at least depth levels deep
+    pub fn get(&self, hash: u64, depth: i32) -> Option<Vec<Evaluation>> {
+        self.lookups.fetch_add(1, Ordering::Relaxed);
+
+        let slot = self.slot(hash)?.lock().unwrap();
+        let entry = slot.as_ref()?;
+        if entry.hash == hash && entry.depth >= depth {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            if entry.generation < self.generation.load(Ordering::Relaxed) {
+                self.reused.fetch_add(1, Ordering::Relaxed);
+            }
+            Some(entry.result.clone())
+        } else {
+            None
+        }
+    }
+
+    // Returns how deep hash was searched, without counting as a lookup
+    pub fn get_depth(&self, hash: u64) -> Option<i32> {
+        let slot = self.slot(hash)?.lock().unwrap();
+        slot.as_ref()
+            .filter(|entry| entry.hash == hash)
+            .map(|entry| entry.depth)
+    }
+
+    // Stores the result of searching hash depth levels deep
+    pub fn insert(&self, hash: u64, depth: i32, result: &[Evaluation]) {
+        if let Some(slot) = self.slot(hash) {
+            let mut slot = slot.lock().unwrap();
+
+            // Keep deeper results for the same position
+            if let Some(entry) = slot.as_ref() {
+                if entry.hash == hash && entry.depth > depth {
+                    return;
+                }
+            }
+
+            *slot = Some(Entry {
+                hash,
+                depth,
+                generation: self.generation.load(Ordering::Relaxed),
+                result: result.to_vec(),
+            });
+        }
+    }
+}
+
+// The statistics, the slots are too many to print
+impl fmt::Debug for TranspositionTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TranspositionTable")
+            .field("size", &self.slots.len())
+            .field("generation", &self.generation)
+            .field("lookups", &self.lookups)
+            .field("hits", &self.hits)
+            .field("reused", &self.reused)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_get() {
+        let table = TranspositionTable::new(16);
+        let result = [
+            Evaluation::new(2, true, 0, 3, 90, 0, 3),
+            Evaluation::default(),
+        ];
+
+        table.insert(42, 3, &result);
+
+        assert_eq!(table.get(42, 2), Some(result.to_vec()));
+        assert_eq!(table.get(42, 4), None);
+        assert_eq!(table.get(58, 1), None);
+        assert_eq!(table.get_hits(), 1);
+        assert_eq!(table.get_lookups(), 3);
+    }
+
+    #[test]
+    fn test_new_search() {
+        let table = TranspositionTable::new(16);
+        let result = [Evaluation::new(2, true, 0, 3, 90, 0, 3)];
+
+        table.insert(42, 3, &result);
+        table.get(42, 3);
+        table.new_search();
+        table.insert(43, 1, &result);
+
+        assert_eq!(table.get_lookups(), 0);
+        assert_eq!(table.get_depth(42), Some(3));
+        assert_eq!(table.get(42, 2), Some(result.to_vec()));
+        assert_eq!(table.get(43, 1), Some(result.to_vec()));
+        assert_eq!(table.get_hits(), 2);
+        // Only the result from before the new search was reused
+        assert_eq!(table.get_reused(), 1);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let table = TranspositionTable::new(0);
+
+        table.insert(42, 3, &[Evaluation::default()]);
+
+        assert_eq!(table.get(42, 3), None);
+        assert_eq!(table.get_hit_rate(), 0.0);
+    }
+}